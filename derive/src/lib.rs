@@ -0,0 +1,514 @@
+// `#[derive(FromJson, ToJson)]` for the conversion traits in
+// `json_parser::json::convert`.
+//
+// Structs with named fields map to objects, newtype structs to their single
+// field, other tuple structs to arrays and unit structs to `null`. Enum unit
+// variants map to their name as a string; other variants to an object whose
+// only member is named after the variant and holds its content.
+//
+// Field attributes:
+//
+//     #[json(rename = "name")]     use `name` as the member name
+//     #[json(default)]             use `Default::default()` when absent
+//     #[json(default = "path")]    call `path()` when absent
+//     #[json(skip)]                never read or written; filled in as with
+//                                  `default`
+//     #[json(flatten)]             read from and write into the enclosing
+//                                  object's members instead of a member
+//
+// A flattened field reads every member not named by one of the struct's
+// own fields, including members another flattened field also reads.
+//
+// Variants accept `rename`. Fields of type `Option<T>` may be absent and
+// then read as `None`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::Attribute;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Generics;
+use syn::LitStr;
+
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_json(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_json(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+enum DefaultAttr {
+    Trait,
+    Path(syn::Path),
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    default: Option<DefaultAttr>,
+    skip: bool,
+    flatten: bool,
+}
+
+struct Field<'a> {
+    ident: &'a syn::Ident,
+    // The variable the field is bound to in generated code, which cannot
+    // clash with the generated code's own variables.
+    binding: syn::Ident,
+    ty: &'a syn::Type,
+    name: String,
+    attrs: FieldAttrs,
+}
+
+fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut parsed = FieldAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                parsed.rename = Some(name.value());
+            } else if meta.path.is_ident("default") {
+                parsed.default = if meta.input.peek(syn::Token![=]) {
+                    let path: LitStr = meta.value()?.parse()?;
+                    Some(DefaultAttr::Path(path.parse()?))
+                } else {
+                    Some(DefaultAttr::Trait)
+                };
+            } else if meta.path.is_ident("skip") {
+                parsed.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                parsed.flatten = true;
+            } else {
+                return Err(meta.error("unknown json attribute"))
+            }
+            Ok(())
+        })?;
+    }
+    if parsed.flatten && (parsed.rename.is_some() || parsed.skip) {
+        return Err(syn::Error::new(Span::call_site(), "`flatten` cannot be combined with `rename` or `skip`"))
+    }
+
+    Ok(parsed)
+}
+
+fn parse_variant_rename(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                rename = Some(name.value());
+                Ok(())
+            } else {
+                Err(meta.error("only `rename` is supported on variants"))
+            }
+        })?;
+    }
+
+    Ok(rename)
+}
+
+fn named_fields(fields: &syn::FieldsNamed) -> syn::Result<Vec<Field<'_>>> {
+    fields
+        .named
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let ident = field.ident.as_ref().expect("named field");
+            let attrs = parse_field_attrs(&field.attrs)?;
+            let name = match attrs.rename {
+                Some(ref name) => name.clone(),
+                None => ident.to_string().trim_start_matches("r#").to_string(),
+            };
+            Ok(Field {
+                ident,
+                binding: format_ident!("__field{}", index),
+                ty: &field.ty,
+                name,
+                attrs,
+            })
+        })
+        .collect()
+}
+
+fn check_unnamed_fields(fields: &syn::FieldsUnnamed) -> syn::Result<()> {
+    for field in &fields.unnamed {
+        if let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("json")) {
+            return Err(syn::Error::new_spanned(attr, "json attributes are only supported on named fields"))
+        }
+    }
+
+    Ok(())
+}
+
+fn add_bounds(generics: &Generics, bound: syn::Path) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+// Builds an object from fields bound to their bindings.
+fn fields_to_object(fields: &[Field]) -> TokenStream2 {
+    let members = fields.iter().filter(|field| !field.attrs.skip).map(|field| {
+        let binding = &field.binding;
+        let name = &field.name;
+        if field.attrs.flatten {
+            quote! {
+                ::json_parser::json::convert::flatten_into(&mut map, ::json_parser::json::ToJson::to_json(#binding));
+            }
+        } else {
+            quote! {
+                map.insert(::std::string::String::from(#name), ::json_parser::json::ToJson::to_json(#binding));
+            }
+        }
+    });
+
+    quote! {
+        {
+            let mut map = ::json_parser::json::Map::new();
+            #(#members)*
+            ::json_parser::json::Value::Object(map)
+        }
+    }
+}
+
+// Reads fields from the object `value` into their bindings.
+fn fields_from_object(fields: &[Field]) -> TokenStream2 {
+    let known: Vec<&str> = fields
+        .iter()
+        .filter(|field| !field.attrs.skip && !field.attrs.flatten)
+        .map(|field| field.name.as_str())
+        .collect();
+    let reads = fields.iter().map(|field| {
+        let binding = &field.binding;
+        let ty = field.ty;
+        let name = &field.name;
+        let default = match field.attrs.default {
+            Some(DefaultAttr::Trait) => Some(quote!(::std::default::Default::default())),
+            Some(DefaultAttr::Path(ref path)) => Some(quote!(#path())),
+            None => None,
+        };
+        if field.attrs.skip {
+            let default = default.unwrap_or_else(|| quote!(::std::default::Default::default()));
+            return quote! {
+                let #binding: #ty = #default;
+            }
+        }
+        if field.attrs.flatten {
+            return quote! {
+                let #binding = <#ty as ::json_parser::json::FromJson>::from_json(
+                    &::json_parser::json::convert::remaining(map, &[#(#known),*]),
+                )?;
+            }
+        }
+        let missing = match default {
+            Some(default) => {
+                default
+            }
+            None => {
+                quote! {
+                    match <#ty as ::json_parser::json::FromJson>::from_missing() {
+                        ::std::option::Option::Some(value) => value,
+                        ::std::option::Option::None => {
+                            return ::std::result::Result::Err(::json_parser::json::convert::missing_field(#name))
+                        }
+                    }
+                }
+            }
+        };
+        let context = format!("field `{}`", name);
+        quote! {
+            let #binding = match map.get(#name) {
+                ::std::option::Option::Some(value) => {
+                    <#ty as ::json_parser::json::FromJson>::from_json(value)
+                        .map_err(|err| ::json_parser::json::convert::context(err, #context))?
+                }
+                ::std::option::Option::None => {
+                    #missing
+                }
+            };
+        }
+    });
+
+    quote! {
+        let map = match *value {
+            ::json_parser::json::Value::Object(ref map) => map,
+            ::json_parser::json::Value::Raw(ref raw) => return ::json_parser::json::convert::from_raw(raw),
+            _ => return ::std::result::Result::Err(::json_parser::json::convert::invalid_type("object", value)),
+        };
+        #(#reads)*
+    }
+}
+
+// `ident: binding` for each field, to destructure or build a value.
+fn members(fields: &[Field]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident;
+            let binding = &field.binding;
+            quote!(#ident: #binding)
+        })
+        .collect()
+}
+
+fn bindings(len: usize) -> Vec<syn::Ident> {
+    (0..len).map(|index| format_ident!("__field{}", index)).collect()
+}
+
+fn elements_to_array(bindings: &[syn::Ident]) -> TokenStream2 {
+    quote! {
+        ::json_parser::json::Value::Array(::std::vec![#(::json_parser::json::ToJson::to_json(#bindings)),*])
+    }
+}
+
+// Reads the elements of an array of exactly `bindings.len()` elements into
+// the bindings.
+fn elements_from_array(bindings: &[syn::Ident]) -> TokenStream2 {
+    let len = bindings.len();
+    let expected = format!("array of length {}", len);
+    let reads = bindings.iter().enumerate().map(|(index, binding)| {
+        let context = format!("index {}", index);
+        quote! {
+            let #binding = ::json_parser::json::FromJson::from_json(&list[#index])
+                .map_err(|err| ::json_parser::json::convert::context(err, #context))?;
+        }
+    });
+
+    quote! {
+        let list = match *value {
+            ::json_parser::json::Value::Array(ref list) if list.len() == #len => list,
+            ::json_parser::json::Value::Raw(ref raw) => return ::json_parser::json::convert::from_raw(raw),
+            _ => return ::std::result::Result::Err(::json_parser::json::convert::invalid_type(#expected, value)),
+        };
+        #(#reads)*
+    }
+}
+
+fn expand_to_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let body = match input.data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => {
+                    let fields = named_fields(fields)?;
+                    let members = members(&fields);
+                    let object = fields_to_object(&fields);
+                    quote! {
+                        let Self { #(#members),* } = self;
+                        #object
+                    }
+                }
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                    check_unnamed_fields(fields)?;
+                    quote!(::json_parser::json::ToJson::to_json(&self.0))
+                }
+                Fields::Unnamed(ref fields) => {
+                    check_unnamed_fields(fields)?;
+                    let bindings = bindings(fields.unnamed.len());
+                    let array = elements_to_array(&bindings);
+                    quote! {
+                        let Self(#(#bindings),*) = self;
+                        #array
+                    }
+                }
+                Fields::Unit => {
+                    quote!(::json_parser::json::Value::Null)
+                }
+            }
+        }
+        Data::Enum(ref data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let name = parse_variant_rename(&variant.attrs)?.unwrap_or_else(|| ident.to_string());
+                let arm = match variant.fields {
+                    Fields::Named(ref fields) => {
+                        let fields = named_fields(fields)?;
+                        let members = members(&fields);
+                        let object = fields_to_object(&fields);
+                        quote!(Self::#ident { #(#members),* } => (#name, #object))
+                    }
+                    Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                        check_unnamed_fields(fields)?;
+                        quote!(Self::#ident(__field0) => (#name, ::json_parser::json::ToJson::to_json(__field0)))
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        check_unnamed_fields(fields)?;
+                        let bindings = bindings(fields.unnamed.len());
+                        let array = elements_to_array(&bindings);
+                        quote!(Self::#ident(#(#bindings),*) => (#name, #array))
+                    }
+                    Fields::Unit => {
+                        quote! {
+                            Self::#ident => {
+                                return ::json_parser::json::Value::String(::std::string::String::from(#name))
+                            }
+                        }
+                    }
+                };
+                arms.push(arm);
+            }
+            quote! {
+                let (name, content): (&str, ::json_parser::json::Value) = match self {
+                    #(#arms,)*
+                };
+                let mut map = ::json_parser::json::Map::new();
+                map.insert(::std::string::String::from(name), content);
+                ::json_parser::json::Value::Object(map)
+            }
+        }
+        Data::Union(ref data) => {
+            return Err(syn::Error::new_spanned(data.union_token, "ToJson cannot be derived for unions"))
+        }
+    };
+
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(::json_parser::json::ToJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::json_parser::json::ToJson for #ident #ty_generics #where_clause {
+            #[allow(unreachable_code)]
+            fn to_json(&self) -> ::json_parser::json::Value {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_from_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let body = match input.data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => {
+                    let fields = named_fields(fields)?;
+                    let members = members(&fields);
+                    let read = fields_from_object(&fields);
+                    quote! {
+                        #read
+                        ::std::result::Result::Ok(Self { #(#members),* })
+                    }
+                }
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                    check_unnamed_fields(fields)?;
+                    quote!(::json_parser::json::FromJson::from_json(value).map(Self))
+                }
+                Fields::Unnamed(ref fields) => {
+                    check_unnamed_fields(fields)?;
+                    let bindings = bindings(fields.unnamed.len());
+                    let read = elements_from_array(&bindings);
+                    quote! {
+                        #read
+                        ::std::result::Result::Ok(Self(#(#bindings),*))
+                    }
+                }
+                Fields::Unit => {
+                    quote! {
+                        match *value {
+                            ::json_parser::json::Value::Null => ::std::result::Result::Ok(Self),
+                            ::json_parser::json::Value::Raw(ref raw) => ::json_parser::json::convert::from_raw(raw),
+                            _ => ::std::result::Result::Err(::json_parser::json::convert::invalid_type("null", value)),
+                        }
+                    }
+                }
+            }
+        }
+        Data::Enum(ref data) => {
+            let mut unit_arms = Vec::new();
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let name = parse_variant_rename(&variant.attrs)?.unwrap_or_else(|| ident.to_string());
+                let read = match variant.fields {
+                    Fields::Named(ref fields) => {
+                        let fields = named_fields(fields)?;
+                        let members = members(&fields);
+                        let read = fields_from_object(&fields);
+                        quote! {
+                            #read
+                            ::std::result::Result::Ok(Self::#ident { #(#members),* })
+                        }
+                    }
+                    Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                        check_unnamed_fields(fields)?;
+                        quote!(::json_parser::json::FromJson::from_json(value).map(Self::#ident))
+                    }
+                    Fields::Unnamed(ref fields) => {
+                        check_unnamed_fields(fields)?;
+                        let bindings = bindings(fields.unnamed.len());
+                        let read = elements_from_array(&bindings);
+                        quote! {
+                            #read
+                            ::std::result::Result::Ok(Self::#ident(#(#bindings),*))
+                        }
+                    }
+                    Fields::Unit => {
+                        unit_arms.push(quote!(#name => ::std::result::Result::Ok(Self::#ident)));
+                        quote! {
+                            <() as ::json_parser::json::FromJson>::from_json(value)?;
+                            ::std::result::Result::Ok(Self::#ident)
+                        }
+                    }
+                };
+                let context = format!("variant `{}`", name);
+                arms.push(quote! {
+                    #name => {
+                        let read = || -> ::std::result::Result<Self, ::json_parser::json::Error> {
+                            #read
+                        };
+                        read().map_err(|err| ::json_parser::json::convert::context(err, #context))
+                    }
+                });
+            }
+            quote! {
+                match *value {
+                    ::json_parser::json::Value::String(ref name) => {
+                        match name.as_str() {
+                            #(#unit_arms,)*
+                            _ => ::std::result::Result::Err(::json_parser::json::convert::unknown_variant(name)),
+                        }
+                    }
+                    ::json_parser::json::Value::Object(ref map) if map.len() == 1 => {
+                        let (name, value) = map.iter().next().expect("one member");
+                        match name.as_str() {
+                            #(#arms)*
+                            _ => ::std::result::Result::Err(::json_parser::json::convert::unknown_variant(name)),
+                        }
+                    }
+                    ::json_parser::json::Value::Raw(ref raw) => {
+                        ::json_parser::json::convert::from_raw(raw)
+                    }
+                    _ => {
+                        ::std::result::Result::Err(::json_parser::json::convert::invalid_type("variant name or single-member object", value))
+                    }
+                }
+            }
+        }
+        Data::Union(ref data) => {
+            return Err(syn::Error::new_spanned(data.union_token, "FromJson cannot be derived for unions"))
+        }
+    };
+
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(::json_parser::json::FromJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::json_parser::json::FromJson for #ident #ty_generics #where_clause {
+            fn from_json(value: &::json_parser::json::Value) -> ::std::result::Result<Self, ::json_parser::json::Error> {
+                #body
+            }
+        }
+    })
+}
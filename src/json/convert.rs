@@ -0,0 +1,429 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasher;
+
+use super::de::Error;
+use super::value::Map;
+use super::value::RawValue;
+use super::value::Value;
+use super::value::index::Type;
+
+#[cfg(feature = "derive")]
+pub use json_parser_derive::FromJson;
+#[cfg(feature = "derive")]
+pub use json_parser_derive::ToJson;
+
+// Conversions between Rust types and `Value` that need neither serde nor
+// any format other than JSON. Errors from `from_json` carry no position,
+// since a `Value` no longer knows where it was parsed from; instead the
+// message names the fields and indices leading to the offending value.
+
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, Error>;
+
+    // The value to use for a struct field that is absent. Only `Option`
+    // has one; everything else makes the field required.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+pub fn to_value<T: ?Sized + ToJson>(value: &T) -> Value {
+    value.to_json()
+}
+
+pub fn from_value<T: FromJson>(value: &Value) -> Result<T, Error> {
+    T::from_json(value)
+}
+
+pub fn invalid_type(expected: &str, found: &Value) -> Error {
+    Error::custom(format!("expected {}, found {}", expected, Type(found)))
+}
+
+pub fn missing_field(name: &str) -> Error {
+    Error::custom(format!("missing field `{}`", name))
+}
+
+pub fn unknown_variant(name: &str) -> Error {
+    Error::custom(format!("unknown variant `{}`", name))
+}
+
+// Reads a `Value::Raw` as the value its text holds. Every `from_json` other
+// than `Value`'s, which keeps it as it is, looks through raw values this way.
+pub fn from_raw<T: FromJson>(raw: &RawValue) -> Result<T, Error> {
+    T::from_json(&raw.to_value()?)
+}
+
+// Prefixes an error from a nested value with where that value sits, e.g.
+// "field `items`: index 2: expected string, found number".
+pub fn context<C: fmt::Display>(err: Error, context: C) -> Error {
+    match err.detail() {
+        Some(detail) if !err.is_located() => Error::custom(format!("{}: {}", context, detail)),
+        _ => err,
+    }
+}
+
+// The members of `map` other than `known`, for a flattened field to read.
+pub fn remaining(map: &Map, known: &[&str]) -> Value {
+    let rest: Map = map
+        .iter()
+        .filter(|&(key, _)| !known.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    Value::Object(rest)
+}
+
+// Merges the members of a flattened field into its parent's object. Values
+// that are not objects, such as `null` from an empty `Option`, add nothing.
+pub fn flatten_into(map: &mut Map, value: Value) {
+    if let Value::Object(members) = value {
+        map.extend(members);
+    }
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Value, Error> {
+        Ok(value.clone())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Boolean(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<bool, Error> {
+        match *value {
+            Value::Boolean(b) => Ok(b),
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => Err(invalid_type("boolean", value)),
+        }
+    }
+}
+
+// 128-bit values outside the 64-bit range come out as the nearest float,
+// unless the `arbitrary_precision` feature keeps them exact.
+macro_rules! impl_integer {
+    ($($ty:ty => $to:ident,)*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+
+            impl FromJson for $ty {
+                fn from_json(value: &Value) -> Result<$ty, Error> {
+                    match *value {
+                        Value::Number(ref number) if number.is_integer() => {
+                            number.$to().map_err(|_| {
+                                Error::custom(format!("{} is out of range for {}", value, stringify!($ty)))
+                            })
+                        }
+                        Value::Raw(ref raw) => {
+                            from_raw(raw)
+                        }
+                        _ => {
+                            Err(invalid_type(stringify!($ty), value))
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer! {
+    i8 => to_i8,
+    i16 => to_i16,
+    i32 => to_i32,
+    i64 => to_i64,
+    i128 => to_i128,
+    isize => to_isize,
+    u8 => to_u8,
+    u16 => to_u16,
+    u32 => to_u32,
+    u64 => to_u64,
+    u128 => to_u128,
+    usize => to_usize,
+}
+
+// A finite number too large for `f32` is an error rather than infinity.
+macro_rules! impl_float {
+    ($($ty:ty)*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+
+            impl FromJson for $ty {
+                fn from_json(value: &Value) -> Result<$ty, Error> {
+                    let n = match *value {
+                        Value::Number(ref number) => number.as_f64(),
+                        Value::Raw(ref raw) => return from_raw(raw),
+                        _ => None,
+                    };
+                    match n {
+                        Some(n) if n.is_finite() && (n as $ty).is_infinite() => {
+                            Err(Error::custom(format!("{} is out of range for {}", value, stringify!($ty))))
+                        }
+                        Some(n) => {
+                            Ok(n as $ty)
+                        }
+                        None => {
+                            Err(invalid_type(stringify!($ty), value))
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32 f64);
+
+impl ToJson for char {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl FromJson for char {
+    fn from_json(value: &Value) -> Result<char, Error> {
+        let str = match *value {
+            Value::String(ref str) => str,
+            Value::Raw(ref raw) => return from_raw(raw),
+            _ => return Err(invalid_type("character", value)),
+        };
+        let mut chars = str.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(Error::custom(format!("expected a single character, found {:?}", str))),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::String(String::from(self))
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<String, Error> {
+        match *value {
+            Value::String(ref str) => Ok(str.clone()),
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => Err(invalid_type("string", value)),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Vec<T>, Error> {
+        let list = match *value {
+            Value::Array(ref list) => list,
+            Value::Raw(ref raw) => return from_raw(raw),
+            _ => return Err(invalid_type("array", value)),
+        };
+        list.iter()
+            .enumerate()
+            .map(|(index, value)| T::from_json(value).map_err(|err| context(err, format_args!("index {}", index))))
+            .collect()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        match *self {
+            Some(ref value) => value.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Option<T>, Error> {
+        match *value {
+            Value::Null => Ok(None),
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => T::from_json(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Option<T>> {
+        Some(None)
+    }
+}
+
+impl<T: ?Sized + ToJson> ToJson for Box<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &Value) -> Result<Box<T>, Error> {
+        T::from_json(value).map(Box::new)
+    }
+
+    fn from_missing() -> Option<Box<T>> {
+        T::from_missing().map(Box::new)
+    }
+}
+
+impl<T: ?Sized + ToJson> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for Map {
+    fn to_json(&self) -> Value {
+        Value::Object(self.clone())
+    }
+}
+
+impl FromJson for Map {
+    fn from_json(value: &Value) -> Result<Map, Error> {
+        match *value {
+            Value::Object(ref map) => Ok(map.clone()),
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => Err(invalid_type("object", value)),
+        }
+    }
+}
+
+// Callers first look through `Value::Raw`, whose members cannot be lent out.
+fn from_members<'v, T: FromJson>(value: &'v Value) -> Result<impl Iterator<Item = Result<(String, T), Error>> + 'v, Error> {
+    let map = match *value {
+        Value::Object(ref map) => map,
+        _ => return Err(invalid_type("object", value)),
+    };
+    Ok(map.iter().map(|(key, value)| {
+        let value = T::from_json(value).map_err(|err| context(err, format_args!("field `{}`", key)))?;
+        Ok((key.clone(), value))
+    }))
+}
+
+impl<T: ToJson, S> ToJson for HashMap<String, T, S> {
+    fn to_json(&self) -> Value {
+        Value::Object(self.iter().map(|(key, value)| (key.clone(), value.to_json())).collect())
+    }
+}
+
+impl<T: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, T, S> {
+    fn from_json(value: &Value) -> Result<HashMap<String, T, S>, Error> {
+        match *value {
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => from_members(value)?.collect(),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Value {
+        Value::Object(self.iter().map(|(key, value)| (key.clone(), value.to_json())).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &Value) -> Result<BTreeMap<String, T>, Error> {
+        match *value {
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => from_members(value)?.collect(),
+        }
+    }
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> Value {
+        Value::Null
+    }
+}
+
+impl FromJson for () {
+    fn from_json(value: &Value) -> Result<(), Error> {
+        match *value {
+            Value::Null => Ok(()),
+            Value::Raw(ref raw) => from_raw(raw),
+            _ => Err(invalid_type("null", value)),
+        }
+    }
+}
+
+// Tuples are arrays of exactly their length.
+macro_rules! impl_tuple {
+    ($($len:expr => ($($index:tt $name:ident)+),)+) => {
+        $(
+            impl<$($name: ToJson),+> ToJson for ($($name,)+) {
+                fn to_json(&self) -> Value {
+                    Value::Array(vec![$(self.$index.to_json()),+])
+                }
+            }
+
+            impl<$($name: FromJson),+> FromJson for ($($name,)+) {
+                fn from_json(value: &Value) -> Result<($($name,)+), Error> {
+                    match *value {
+                        Value::Array(ref list) if list.len() == $len => {
+                            Ok(($(
+                                $name::from_json(&list[$index])
+                                    .map_err(|err| context(err, format_args!("index {}", $index)))?,
+                            )+))
+                        }
+                        Value::Raw(ref raw) => {
+                            from_raw(raw)
+                        }
+                        _ => {
+                            Err(invalid_type(concat!("array of length ", $len), value))
+                        }
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_tuple! {
+    1 => (0 T0),
+    2 => (0 T0 1 T1),
+    3 => (0 T0 1 T1 2 T2),
+    4 => (0 T0 1 T1 2 T2 3 T3),
+    5 => (0 T0 1 T1 2 T2 3 T3 4 T4),
+    6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5),
+    7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6),
+    8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7),
+}
@@ -0,0 +1,285 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedCharacter,
+    UnexpectedEof,
+    InvalidNumber,
+    InvalidEscape,
+    LoneSurrogate,
+    TrailingCharacters,
+    DuplicateKey,
+    DepthExceeded,
+    InputTooLong,
+    StringTooLong,
+    NumberTooLong,
+    TooManyMembers,
+    TooManyElements,
+    InvalidUtf8,
+    Io(io::ErrorKind),
+    // Input that is valid JSON but does not fit the type being read into.
+    Custom,
+}
+
+impl ErrorKind {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            ErrorKind::UnexpectedCharacter => "unexpected character",
+            ErrorKind::UnexpectedEof => "unexpected end of input",
+            ErrorKind::InvalidNumber => "invalid number",
+            ErrorKind::InvalidEscape => "invalid escape",
+            ErrorKind::LoneSurrogate => "lone surrogate in unicode escape",
+            ErrorKind::TrailingCharacters => "trailing characters",
+            ErrorKind::DuplicateKey => "duplicate key",
+            ErrorKind::DepthExceeded => "nesting too deep",
+            ErrorKind::InputTooLong => "input too long",
+            ErrorKind::StringTooLong => "string too long",
+            ErrorKind::NumberTooLong => "number too long",
+            ErrorKind::TooManyMembers => "too many object members",
+            ErrorKind::TooManyElements => "too many array elements",
+            ErrorKind::InvalidUtf8 => "invalid UTF-8",
+            ErrorKind::Io(_) => "io error",
+            ErrorKind::Custom => "invalid data",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::Io(kind) => write!(f, "{}: {}", self.description(), kind),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new() -> Position {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    pub fn at(source: &str, offset: usize) -> Position {
+        let mut position = Position::new();
+        for c in source[..offset].chars() {
+            position.advance(c);
+        }
+        position
+    }
+
+    pub fn advance(&mut self, c: char) {
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+
+    // Byte-at-a-time counterpart of `advance`: columns count characters, so
+    // UTF-8 continuation bytes do not move the column.
+    pub fn advance_byte(&mut self, byte: u8) {
+        self.offset += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if byte & 0xc0 != 0x80 {
+            self.column += 1;
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Position {
+        Position::new()
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    expected: Option<String>,
+    found: Option<char>,
+    detail: Option<String>,
+    position: Position,
+    previous: Option<Position>,
+    located: bool,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, expected: Option<String>, found: Option<char>, position: Position) -> Error {
+        Error {
+            kind,
+            expected,
+            found,
+            detail: None,
+            position,
+            previous: None,
+            located: true,
+        }
+    }
+
+    // An error raised while converting already parsed input, described by
+    // `message`. It has no position until `locate` gives it one.
+    pub fn custom<T: fmt::Display>(message: T) -> Error {
+        Error {
+            kind: ErrorKind::Custom,
+            expected: None,
+            found: None,
+            detail: Some(message.to_string()),
+            position: Position::new(),
+            previous: None,
+            located: false,
+        }
+    }
+
+    // Sets the position of an error that has none yet; errors that already
+    // know where they happened keep their position.
+    pub fn locate(mut self, position: Position) -> Error {
+        if !self.located {
+            self.position = position;
+            self.located = true;
+        }
+        self
+    }
+
+    pub fn is_located(&self) -> bool {
+        self.located
+    }
+
+    pub fn with_detail(mut self, detail: String) -> Error {
+        self.detail = Some(detail);
+        self
+    }
+
+    pub fn with_previous(mut self, previous: Position) -> Error {
+        self.previous = Some(previous);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    pub fn found(&self) -> Option<char> {
+        self.found
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    // Where the conflicting earlier occurrence is, for errors such as
+    // `DuplicateKey` that involve two places in the document.
+    pub fn previous(&self) -> Option<Position> {
+        self.previous
+    }
+
+    pub fn offset(&self) -> usize {
+        self.position.offset
+    }
+
+    pub fn line(&self) -> usize {
+        self.position.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.position.column
+    }
+
+    fn message(&self) -> String {
+        let mut message = self.kind.to_string();
+        if let Some(ref detail) = self.detail {
+            message.push_str(": ");
+            message.push_str(detail);
+        }
+        if let Some(ref expected) = self.expected {
+            message.push_str(", expected ");
+            message.push_str(expected);
+        }
+        if let Some(found) = self.found {
+            message.push_str(&format!(", found {:?}", found));
+        }
+        message
+    }
+
+    // Renders the error against the document it came from, rustc-style:
+    //
+    //     error: unexpected character, expected ':', found '1'
+    //      --> line 1 column 6
+    //       |
+    //     1 | {"a" 1}
+    //       |      ^
+    pub fn render(&self, source: &str) -> String {
+        if !self.located {
+            return format!("error: {}\n", self.message())
+        }
+        let line = source.split('\n').nth(self.position.line - 1).unwrap_or("");
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let number = self.position.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let caret: String = line
+            .chars()
+            .take(self.position.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let mut rendered = format!(
+            "error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}^\n",
+            self.message(),
+            gutter,
+            self.position,
+            gutter,
+            number,
+            line,
+            gutter,
+            caret,
+        );
+        if let Some(previous) = self.previous {
+            rendered.push_str(&format!("{} = note: previously at {}\n", gutter, previous));
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message())?;
+        if self.located {
+            write!(f, " at {}", self.position)?;
+        }
+        if let Some(previous) = self.previous {
+            write!(f, ", previously at {}", previous)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {}
@@ -0,0 +1,591 @@
+use std::borrow::Cow;
+use std::io;
+use std::str;
+
+use super::value::BorrowedValue;
+use super::value::PointerError;
+use super::value::RawValue;
+use super::value::pointer;
+use super::value::Value;
+use super::value::Map;
+
+pub mod error;
+pub mod options;
+pub mod read;
+pub mod parser;
+pub mod push;
+pub mod stream;
+
+pub use error::Error;
+pub use error::ErrorKind;
+pub use error::Position;
+pub use options::Duplicate;
+pub use options::DuplicateKeys;
+pub use options::ParserOptions;
+use options::BorrowedMembers;
+use options::Members;
+pub use read::Read;
+pub use read::StrRead;
+pub use read::IoRead;
+pub use parser::Parser;
+pub use parser::Event;
+pub use parser::PathSegment;
+pub use push::PushParser;
+pub use push::Status;
+pub use stream::StreamDeserializer;
+
+// Builds `Value` trees from the events of a `Parser`.
+pub struct De<'a, R = StrRead<'a>> {
+    parser: Parser<'a, R>,
+    duplicates: Vec<Duplicate>,
+    raw: Vec<Vec<String>>,
+    // The location of the value being built, only tracked when `raw` is
+    // not empty.
+    path: Vec<String>,
+}
+
+impl<'a> De<'a, StrRead<'a>> {
+    pub fn new(str: &'a str) -> De<'a, StrRead<'a>> {
+        De::with_options(str, ParserOptions::new())
+    }
+
+    pub fn with_options(str: &'a str, options: ParserOptions) -> De<'a, StrRead<'a>> {
+        De::from_parser(Parser::with_options(StrRead::new(str), options))
+    }
+}
+
+impl<'a, R: io::Read> De<'a, IoRead<R>> {
+    pub fn from_reader(reader: R) -> De<'a, IoRead<R>> {
+        De::from_reader_with(reader, ParserOptions::new())
+    }
+
+    pub fn from_reader_with(reader: R, options: ParserOptions) -> De<'a, IoRead<R>> {
+        De::from_parser(Parser::from_reader_with(reader, options))
+    }
+}
+
+impl<'a, R: Read<'a>> De<'a, R> {
+    pub fn from_parser(parser: Parser<'a, R>) -> De<'a, R> {
+        De {
+            parser,
+            duplicates: Vec::new(),
+            raw: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    // Keeps the value at `pointer` as a `Value::Raw` holding its original
+    // text instead of building it. The value is still checked against the
+    // grammar, but duplicate keys inside it are not looked for. Under
+    // relaxed options the text is written out again as strict JSON instead.
+    pub fn raw_pointer(mut self, pointer: &str) -> Result<De<'a, R>, PointerError> {
+        self.raw.push(pointer::parse_pointer(pointer)?);
+        Ok(self)
+    }
+
+    pub fn parser(&self) -> &Parser<'a, R> {
+        &self.parser
+    }
+
+    pub fn into_parser(self) -> Parser<'a, R> {
+        self.parser
+    }
+
+    pub fn options(&self) -> &ParserOptions {
+        self.parser.options()
+    }
+
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    pub fn take_duplicates(&mut self) -> Vec<Duplicate> {
+        std::mem::take(&mut self.duplicates)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.parser.position().offset
+    }
+
+    pub fn position(&self) -> Position {
+        self.parser.position()
+    }
+
+    pub fn deserialization(&mut self) -> Result<Value, Error> {
+        let value = self.match_value()?;
+        self.match_end()?;
+
+        Ok(value)
+    }
+
+    pub fn match_end(&mut self) -> Result<(), Error> {
+        match self.parser.next_event()? {
+            Some(_) => Err(Error::new(ErrorKind::TrailingCharacters, None, None, self.parser.event_position())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn match_value(&mut self) -> Result<Value, Error> {
+        let raw = self.is_raw();
+        if raw {
+            self.parser.begin_raw();
+        }
+        match self.parser.next_event()? {
+            Some(event) if raw => {
+                self.match_raw_event(event)
+            }
+            Some(event) => {
+                self.match_event(event)
+            }
+            None => {
+                Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("value")), None, self.position()))
+            }
+        }
+    }
+
+    pub fn match_event(&mut self, event: Event<'a>) -> Result<Value, Error> {
+        match event {
+            Event::StartObject | Event::StartArray if self.parser.options().explicit_stack => {
+                self.match_nested(event)
+            }
+            Event::StartObject => {
+                Ok(Value::Object(self.match_members()?))
+            }
+            Event::StartArray => {
+                Ok(Value::Array(self.match_elements()?))
+            }
+            Event::String(str) => {
+                Ok(Value::String(str.into_owned()))
+            }
+            Event::Number(number) => {
+                Ok(Value::Number(number))
+            }
+            Event::Bool(b) => {
+                Ok(Value::Boolean(b))
+            }
+            Event::Null => {
+                Ok(Value::Null)
+            }
+            Event::Key(_) | Event::EndObject | Event::EndArray => {
+                Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("value")), None, self.parser.event_position()))
+            }
+        }
+    }
+
+    // Called after `StartObject`; consumes members up to `EndObject`.
+    pub fn match_members(&mut self) -> Result<Map, Error> {
+        let mut members = Members::new();
+        loop {
+            match self.parser.next_event()? {
+                Some(Event::Key(key)) => {
+                    let position = self.parser.event_position();
+                    if !self.raw.is_empty() {
+                        self.path.push(String::from(&*key));
+                    }
+                    let value = self.match_value()?;
+                    self.path.pop();
+                    let policy = self.parser.options().duplicate_keys;
+                    members.insert(policy, &mut self.duplicates, key.into_owned(), value, position)?;
+                }
+                Some(Event::EndObject) => {
+                    break Ok(members.into_map())
+                }
+                _ => {
+                    break Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("key")), None, self.parser.event_position()))
+                }
+            }
+        }
+    }
+
+    // Called after `StartArray`; consumes elements up to `EndArray`.
+    pub fn match_elements(&mut self) -> Result<Vec<Value>, Error> {
+        let mut list: Vec<Value> = Vec::new();
+        loop {
+            // The element is only known to exist once its first event has
+            // been read, so recording has to start before that.
+            if !self.raw.is_empty() {
+                self.path.push(list.len().to_string());
+            }
+            let raw = self.is_raw();
+            if raw {
+                self.parser.begin_raw();
+            }
+            let value = match self.parser.next_event()? {
+                Some(Event::EndArray) => {
+                    self.path.pop();
+                    if raw {
+                        self.parser.end_raw();
+                    }
+                    break Ok(list)
+                }
+                Some(event) if raw => {
+                    self.match_raw_event(event)?
+                }
+                Some(event) => {
+                    self.match_event(event)?
+                }
+                None => {
+                    break Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("']'")), None, self.position()))
+                }
+            };
+            self.path.pop();
+            list.push(value);
+        }
+    }
+
+    // Builds the object or array opened by `event` like `match_members` and
+    // `match_elements` do, but keeps the unfinished containers in a list
+    // instead of recursing; see `ParserOptions::explicit_stack`.
+    fn match_nested(&mut self, event: Event<'a>) -> Result<Value, Error> {
+        let mut stack = vec![Partial::open(&event)];
+        loop {
+            // The first event of the next member or element, or `None` when
+            // the innermost container has ended.
+            let (event, raw) = match stack.last_mut() {
+                Some(Partial::Object(_, current)) => {
+                    match self.parser.next_event()? {
+                        Some(Event::Key(key)) => {
+                            if !self.raw.is_empty() {
+                                self.path.push(String::from(&*key));
+                            }
+                            *current = Some((key.into_owned(), self.parser.event_position()));
+                            let raw = self.is_raw();
+                            if raw {
+                                self.parser.begin_raw();
+                            }
+                            match self.parser.next_event()? {
+                                Some(event) => (Some(event), raw),
+                                None => return Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("value")), None, self.position())),
+                            }
+                        }
+                        Some(Event::EndObject) => {
+                            (None, false)
+                        }
+                        _ => {
+                            return Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("key")), None, self.parser.event_position()))
+                        }
+                    }
+                }
+                Some(Partial::Array(list)) => {
+                    if !self.raw.is_empty() {
+                        self.path.push(list.len().to_string());
+                    }
+                    let raw = self.is_raw();
+                    if raw {
+                        self.parser.begin_raw();
+                    }
+                    match self.parser.next_event()? {
+                        Some(Event::EndArray) => {
+                            self.path.pop();
+                            if raw {
+                                self.parser.end_raw();
+                            }
+                            (None, false)
+                        }
+                        Some(event) => {
+                            (Some(event), raw)
+                        }
+                        None => {
+                            return Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("']'")), None, self.position()))
+                        }
+                    }
+                }
+                None => {
+                    unreachable!()
+                }
+            };
+
+            let value = match event {
+                None => {
+                    match stack.pop() {
+                        Some(Partial::Object(members, _)) => Value::Object(members.into_map()),
+                        Some(Partial::Array(list)) => Value::Array(list),
+                        None => unreachable!(),
+                    }
+                }
+                Some(event) if raw => {
+                    self.match_raw_event(event)?
+                }
+                Some(event @ Event::StartObject) | Some(event @ Event::StartArray) => {
+                    stack.push(Partial::open(&event));
+                    continue
+                }
+                Some(event) => {
+                    self.match_event(event)?
+                }
+            };
+
+            // Hand the finished value to its container.
+            self.path.pop();
+            match stack.last_mut() {
+                Some(Partial::Object(members, current)) => {
+                    let (key, position) = current.take().expect("object member without key");
+                    let policy = self.parser.options().duplicate_keys;
+                    members.insert(policy, &mut self.duplicates, key, value, position)?;
+                }
+                Some(Partial::Array(list)) => {
+                    list.push(value);
+                }
+                None => {
+                    return Ok(value)
+                }
+            }
+        }
+    }
+
+    // Skips over the rest of a value whose first event has been read while
+    // recording its text. Text read under relaxed options may not be JSON,
+    // so there the value is built and written out again as strict JSON,
+    // non-finite numbers becoming `null` as they do when serialized.
+    fn match_raw_event(&mut self, event: Event<'a>) -> Result<Value, Error> {
+        match event {
+            Event::Key(_) | Event::EndObject | Event::EndArray => {
+                self.parser.end_raw();
+                return Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("value")), None, self.parser.event_position()))
+            }
+            _ if self.parser.options().is_relaxed() => {
+                self.parser.end_raw();
+                let value = self.match_event(event)?;
+                return Ok(Value::Raw(RawValue::from_validated(value.to_string())))
+            }
+            Event::StartObject | Event::StartArray => {
+                let depth = self.parser.depth();
+                while self.parser.depth() >= depth {
+                    if self.parser.next_event()?.is_none() {
+                        return Err(Error::new(ErrorKind::UnexpectedEof, None, None, self.position()))
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(Value::Raw(RawValue::from_validated(self.parser.end_raw().into_owned())))
+    }
+
+    fn is_raw(&self) -> bool {
+        self.raw.contains(&self.path)
+    }
+
+    // Like `deserialization`, but builds a `BorrowedValue` whose strings
+    // borrow from the input wherever the parser can hand them out borrowed.
+    pub fn borrowed_deserialization(&mut self) -> Result<BorrowedValue<'a>, Error> {
+        let value = self.match_borrowed_value()?;
+        self.match_end()?;
+
+        Ok(value)
+    }
+
+    pub fn match_borrowed_value(&mut self) -> Result<BorrowedValue<'a>, Error> {
+        match self.parser.next_event()? {
+            Some(event) => {
+                self.match_borrowed_event(event)
+            }
+            None => {
+                Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("value")), None, self.position()))
+            }
+        }
+    }
+
+    pub fn match_borrowed_event(&mut self, event: Event<'a>) -> Result<BorrowedValue<'a>, Error> {
+        match event {
+            Event::StartObject | Event::StartArray if self.parser.options().explicit_stack => {
+                self.match_borrowed_nested(event)
+            }
+            Event::StartObject => {
+                Ok(BorrowedValue::Object(self.match_borrowed_members()?))
+            }
+            Event::StartArray => {
+                Ok(BorrowedValue::Array(self.match_borrowed_elements()?))
+            }
+            Event::String(str) => {
+                Ok(BorrowedValue::String(str))
+            }
+            Event::Number(number) => {
+                Ok(BorrowedValue::Number(number))
+            }
+            Event::Bool(b) => {
+                Ok(BorrowedValue::Boolean(b))
+            }
+            Event::Null => {
+                Ok(BorrowedValue::Null)
+            }
+            Event::Key(_) | Event::EndObject | Event::EndArray => {
+                Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("value")), None, self.parser.event_position()))
+            }
+        }
+    }
+
+    // The duplicate key policy is applied as in `match_members`; a member
+    // that replaces an earlier one keeps the earlier one's place.
+    pub fn match_borrowed_members(&mut self) -> Result<Vec<(Cow<'a, str>, BorrowedValue<'a>)>, Error> {
+        let mut members = BorrowedMembers::new();
+        loop {
+            match self.parser.next_event()? {
+                Some(Event::Key(key)) => {
+                    let position = self.parser.event_position();
+                    let value = self.match_borrowed_value()?;
+                    let policy = self.parser.options().duplicate_keys;
+                    members.insert(policy, &mut self.duplicates, key, value, position)?;
+                }
+                Some(Event::EndObject) => {
+                    break Ok(members.into_vec())
+                }
+                _ => {
+                    break Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("key")), None, self.parser.event_position()))
+                }
+            }
+        }
+    }
+
+    // The `BorrowedValue` counterpart of `match_nested`.
+    fn match_borrowed_nested(&mut self, event: Event<'a>) -> Result<BorrowedValue<'a>, Error> {
+        let mut stack = vec![BorrowedPartial::open(&event)];
+        loop {
+            let event = match stack.last_mut() {
+                Some(BorrowedPartial::Object(_, current)) => {
+                    match self.parser.next_event()? {
+                        Some(Event::Key(key)) => {
+                            *current = Some((key, self.parser.event_position()));
+                            match self.parser.next_event()? {
+                                Some(event) => Some(event),
+                                None => return Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("value")), None, self.position())),
+                            }
+                        }
+                        Some(Event::EndObject) => {
+                            None
+                        }
+                        _ => {
+                            return Err(Error::new(ErrorKind::UnexpectedCharacter, Some(String::from("key")), None, self.parser.event_position()))
+                        }
+                    }
+                }
+                Some(BorrowedPartial::Array(_)) => {
+                    match self.parser.next_event()? {
+                        Some(Event::EndArray) => {
+                            None
+                        }
+                        Some(event) => {
+                            Some(event)
+                        }
+                        None => {
+                            return Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("']'")), None, self.position()))
+                        }
+                    }
+                }
+                None => {
+                    unreachable!()
+                }
+            };
+
+            let value = match event {
+                None => {
+                    match stack.pop() {
+                        Some(BorrowedPartial::Object(members, _)) => BorrowedValue::Object(members.into_vec()),
+                        Some(BorrowedPartial::Array(list)) => BorrowedValue::Array(list),
+                        None => unreachable!(),
+                    }
+                }
+                Some(event @ Event::StartObject) | Some(event @ Event::StartArray) => {
+                    stack.push(BorrowedPartial::open(&event));
+                    continue
+                }
+                Some(event) => {
+                    self.match_borrowed_event(event)?
+                }
+            };
+
+            match stack.last_mut() {
+                Some(BorrowedPartial::Object(members, current)) => {
+                    let (key, position) = current.take().expect("object member without key");
+                    let policy = self.parser.options().duplicate_keys;
+                    members.insert(policy, &mut self.duplicates, key, value, position)?;
+                }
+                Some(BorrowedPartial::Array(list)) => {
+                    list.push(value);
+                }
+                None => {
+                    return Ok(value)
+                }
+            }
+        }
+    }
+
+    pub fn match_borrowed_elements(&mut self) -> Result<Vec<BorrowedValue<'a>>, Error> {
+        let mut list: Vec<BorrowedValue<'a>> = Vec::new();
+        loop {
+            match self.parser.next_event()? {
+                Some(Event::EndArray) => {
+                    break Ok(list)
+                }
+                Some(event) => {
+                    list.push(self.match_borrowed_event(event)?);
+                }
+                None => {
+                    break Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("']'")), None, self.position()))
+                }
+            }
+        }
+    }
+}
+
+// An object or array that `De::match_nested` has yet to finish, with the
+// key of the member being read.
+enum Partial {
+    Object(Members, Option<(String, Position)>),
+    Array(Vec<Value>),
+}
+
+impl Partial {
+    fn open(event: &Event<'_>) -> Partial {
+        match *event {
+            Event::StartObject => Partial::Object(Members::new(), None),
+            _ => Partial::Array(Vec::new()),
+        }
+    }
+}
+
+enum BorrowedPartial<'a> {
+    Object(BorrowedMembers<'a>, Option<(Cow<'a, str>, Position)>),
+    Array(Vec<BorrowedValue<'a>>),
+}
+
+impl<'a> BorrowedPartial<'a> {
+    fn open(event: &Event<'a>) -> BorrowedPartial<'a> {
+        match *event {
+            Event::StartObject => BorrowedPartial::Object(BorrowedMembers::new(), None),
+            _ => BorrowedPartial::Array(Vec::new()),
+        }
+    }
+}
+
+// Parses without copying strings that need no unescaping; see
+// `BorrowedValue`.
+pub fn from_str_borrowed(str: &str) -> Result<BorrowedValue<'_>, Error> {
+    De::new(str).borrowed_deserialization()
+}
+
+pub fn from_str(str: &str) -> Result<Value, Error> {
+    De::new(str).deserialization()
+}
+
+pub fn from_slice(slice: &[u8]) -> Result<Value, Error> {
+    from_str(from_utf8(slice)?)
+}
+
+// Checks the input is UTF-8, reporting where it stops being so.
+pub(crate) fn from_utf8(slice: &[u8]) -> Result<&str, Error> {
+    match str::from_utf8(slice) {
+        Ok(str) => {
+            Ok(str)
+        }
+        Err(err) => {
+            let valid = err.valid_up_to();
+            let prefix = str::from_utf8(&slice[..valid]).unwrap_or("");
+            Err(Error::new(ErrorKind::InvalidUtf8, None, None, Position::at(prefix, valid)))
+        }
+    }
+}
+
+pub fn from_reader<R: io::Read>(reader: R) -> Result<Value, Error> {
+    De::from_reader(reader).deserialization()
+}
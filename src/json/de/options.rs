@@ -0,0 +1,326 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::Error;
+use super::ErrorKind;
+use super::Position;
+use super::super::value::BorrowedValue;
+use super::super::value::Map;
+use super::super::value::Value;
+
+// How an object that repeats a key is handled. Parsers disagree on which
+// occurrence wins, so a document relying on either can be read differently
+// by two services; `Error` refuses such documents outright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    #[default]
+    LastWins,
+    FirstWins,
+    Error,
+    // Keeps the first occurrence in the object and records every later one
+    // in `De::duplicates`.
+    Collect,
+}
+
+// Limits guard against hostile input: each one rejects a document as soon
+// as it is exceeded, with its own `ErrorKind`. Only the nesting depth is
+// limited by default; the others allow anything until set. Lengths are in
+// bytes of the input, so a string is measured as written, escapes included.
+#[derive(Debug, Clone)]
+pub struct ParserOptions {
+    pub duplicate_keys: DuplicateKeys,
+    // Arrays and objects nested deeper than this are refused. Building a
+    // `Value` recurses once per level, so this also bounds stack use.
+    pub max_depth: usize,
+    // The total input, across all values when reading several.
+    pub max_input_len: usize,
+    pub max_string_len: usize,
+    pub max_number_len: usize,
+    // Members of a single object, counting repeated keys each time.
+    pub max_members: usize,
+    // Elements of a single array.
+    pub max_elements: usize,
+    // Builds values keeping unfinished containers on a heap-allocated
+    // stack rather than recursing, for documents nested deeper than the
+    // call stack allows. Raise `max_depth` to make use of it. Everything
+    // else done with such a value still recurses once per level: dropping
+    // it (use `drop_iteratively` instead), cloning, comparing, formatting,
+    // serializing and `into_owned`. The serde `Deserializer` ignores this
+    // option, since the types it builds recurse on their own.
+    pub explicit_stack: bool,
+    // Relaxations of the grammar for hand-edited files, in the spirit of
+    // JSON5. Each is independent and off by default, leaving strict RFC 8259.
+    // They apply to `Parser` and what is built on it; `PushParser` always
+    // reads strict JSON.
+    //
+    // `//` line and `/* */` block comments wherever whitespace may appear.
+    pub comments: bool,
+    // A comma after the last element of an array or member of an object.
+    pub trailing_commas: bool,
+    // Strings and keys in single quotes, in which `"` needs no escape and
+    // `\'` is an escape for `'`; the escape is also accepted in `"` strings.
+    pub single_quotes: bool,
+    // Keys written as identifiers: a letter, `_` or `$`, followed by any of
+    // those or digits.
+    pub unquoted_keys: bool,
+    // `NaN`, `Infinity` and `-Infinity` as numbers.
+    pub nan_infinity: bool,
+    // Integers such as `0x1F` or `-0xff`, within the 64-bit range.
+    pub hex_numbers: bool,
+    // A `+` before a number.
+    pub leading_plus: bool,
+    // A number starting with its decimal point, such as `.5`.
+    pub leading_decimal_point: bool,
+}
+
+impl ParserOptions {
+    pub fn new() -> ParserOptions {
+        ParserOptions::default()
+    }
+
+    pub fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> ParserOptions {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> ParserOptions {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_input_len(mut self, max_input_len: usize) -> ParserOptions {
+        self.max_input_len = max_input_len;
+        self
+    }
+
+    pub fn max_string_len(mut self, max_string_len: usize) -> ParserOptions {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    pub fn max_number_len(mut self, max_number_len: usize) -> ParserOptions {
+        self.max_number_len = max_number_len;
+        self
+    }
+
+    pub fn max_members(mut self, max_members: usize) -> ParserOptions {
+        self.max_members = max_members;
+        self
+    }
+
+    pub fn max_elements(mut self, max_elements: usize) -> ParserOptions {
+        self.max_elements = max_elements;
+        self
+    }
+
+    pub fn explicit_stack(mut self, explicit_stack: bool) -> ParserOptions {
+        self.explicit_stack = explicit_stack;
+        self
+    }
+
+    pub fn comments(mut self, comments: bool) -> ParserOptions {
+        self.comments = comments;
+        self
+    }
+
+    pub fn trailing_commas(mut self, trailing_commas: bool) -> ParserOptions {
+        self.trailing_commas = trailing_commas;
+        self
+    }
+
+    pub fn single_quotes(mut self, single_quotes: bool) -> ParserOptions {
+        self.single_quotes = single_quotes;
+        self
+    }
+
+    pub fn unquoted_keys(mut self, unquoted_keys: bool) -> ParserOptions {
+        self.unquoted_keys = unquoted_keys;
+        self
+    }
+
+    pub fn nan_infinity(mut self, nan_infinity: bool) -> ParserOptions {
+        self.nan_infinity = nan_infinity;
+        self
+    }
+
+    pub fn hex_numbers(mut self, hex_numbers: bool) -> ParserOptions {
+        self.hex_numbers = hex_numbers;
+        self
+    }
+
+    pub fn leading_plus(mut self, leading_plus: bool) -> ParserOptions {
+        self.leading_plus = leading_plus;
+        self
+    }
+
+    pub fn leading_decimal_point(mut self, leading_decimal_point: bool) -> ParserOptions {
+        self.leading_decimal_point = leading_decimal_point;
+        self
+    }
+
+    // Whether any of the relaxations is on, so the input may not be JSON.
+    pub fn is_relaxed(&self) -> bool {
+        self.comments
+            || self.trailing_commas
+            || self.single_quotes
+            || self.unquoted_keys
+            || self.nan_infinity
+            || self.hex_numbers
+            || self.leading_plus
+            || self.leading_decimal_point
+    }
+}
+
+impl Default for ParserOptions {
+    fn default() -> ParserOptions {
+        ParserOptions {
+            duplicate_keys: DuplicateKeys::default(),
+            max_depth: 128,
+            max_input_len: usize::MAX,
+            max_string_len: usize::MAX,
+            max_number_len: usize::MAX,
+            max_members: usize::MAX,
+            max_elements: usize::MAX,
+            explicit_stack: false,
+            comments: false,
+            trailing_commas: false,
+            single_quotes: false,
+            unquoted_keys: false,
+            nan_infinity: false,
+            hex_numbers: false,
+            leading_plus: false,
+            leading_decimal_point: false,
+        }
+    }
+}
+
+pub struct Duplicate {
+    pub key: String,
+    pub value: Value,
+    pub position: Position,
+    pub previous: Position,
+}
+
+// An object under construction, applying a `DuplicateKeys` policy as its
+// members arrive.
+pub(crate) struct Members {
+    map: Map,
+    positions: HashMap<String, Position>,
+}
+
+impl Members {
+    pub fn new() -> Members {
+        Members {
+            map: Map::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn insert(
+        &mut self,
+        policy: DuplicateKeys,
+        duplicates: &mut Vec<Duplicate>,
+        key: String,
+        value: Value,
+        position: Position,
+    ) -> Result<(), Error> {
+        match policy {
+            DuplicateKeys::LastWins => {
+                self.map.insert(key, value);
+            }
+            DuplicateKeys::FirstWins => {
+                if !self.map.contains_key(&key) {
+                    self.map.insert(key, value);
+                }
+            }
+            DuplicateKeys::Error | DuplicateKeys::Collect => {
+                match self.positions.get(&key) {
+                    Some(&previous) if policy == DuplicateKeys::Error => {
+                        return Err(duplicate_error(&key, position, previous))
+                    }
+                    Some(&previous) => {
+                        duplicates.push(Duplicate {
+                            key,
+                            value,
+                            position,
+                            previous,
+                        });
+                    }
+                    None => {
+                        self.positions.insert(key.clone(), position);
+                        self.map.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn into_map(self) -> Map {
+        self.map
+    }
+}
+
+// The `BorrowedValue` counterpart of `Members`, keeping members in input
+// order; a member that replaces an earlier one takes its place.
+pub(crate) struct BorrowedMembers<'a> {
+    members: Vec<(Cow<'a, str>, BorrowedValue<'a>)>,
+    seen: HashMap<Cow<'a, str>, (usize, Position)>,
+}
+
+impl<'a> BorrowedMembers<'a> {
+    pub fn new() -> BorrowedMembers<'a> {
+        BorrowedMembers {
+            members: Vec::new(),
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn insert(
+        &mut self,
+        policy: DuplicateKeys,
+        duplicates: &mut Vec<Duplicate>,
+        key: Cow<'a, str>,
+        value: BorrowedValue<'a>,
+        position: Position,
+    ) -> Result<(), Error> {
+        let (index, previous) = match self.seen.get(&key) {
+            Some(&seen) => seen,
+            None => {
+                self.seen.insert(key.clone(), (self.members.len(), position));
+                self.members.push((key, value));
+                return Ok(())
+            }
+        };
+        match policy {
+            DuplicateKeys::LastWins => {
+                self.members[index].1 = value;
+            }
+            DuplicateKeys::FirstWins => {}
+            DuplicateKeys::Error => {
+                return Err(duplicate_error(&key, position, previous))
+            }
+            DuplicateKeys::Collect => {
+                duplicates.push(Duplicate {
+                    key: key.into_owned(),
+                    value: value.into_owned(),
+                    position,
+                    previous,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn into_vec(self) -> Vec<(Cow<'a, str>, BorrowedValue<'a>)> {
+        self.members
+    }
+}
+
+pub(crate) fn duplicate_error(key: &str, position: Position, previous: Position) -> Error {
+    Error::new(ErrorKind::DuplicateKey, None, None, position)
+        .with_detail(format!("{:?}", key))
+        .with_previous(previous)
+}
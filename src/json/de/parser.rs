@@ -0,0 +1,984 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+
+use super::Error;
+use super::ErrorKind;
+use super::ParserOptions;
+use super::Position;
+use super::read::IoRead;
+use super::read::Read;
+use super::read::StrRead;
+use super::super::value::Number;
+use super::super::value::Num;
+use super::super::value::pointer;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    Key(Cow<'a, str>),
+    EndObject,
+    StartArray,
+    EndArray,
+    String(Cow<'a, str>),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+// Recording of the input for `begin_raw`/`end_raw`. Sources that can hand
+// out slices only remember where the value starts.
+enum Capture {
+    Off,
+    Pending,
+    Slice(usize),
+    Buffer(String),
+}
+
+// The key or index of the current member, and for objects how many members
+// have been read.
+enum Container {
+    Object(Option<String>, usize),
+    Array(Option<usize>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Value,
+    ObjectStart,
+    ObjectKey,
+    ObjectComma,
+    ArrayStart,
+    ArrayComma,
+    End,
+    Done,
+}
+
+// The JSON grammar as a pull parser: each call to `next_event` scans just
+// enough input to produce one event. Nesting is tracked on an explicit stack
+// rather than by recursion, so the parser itself never grows the call stack.
+pub struct Parser<'a, R> {
+    read: R,
+    peek: Option<char>,
+    position: Position,
+    start: Position,
+    options: ParserOptions,
+    stack: Vec<Container>,
+    state: State,
+    multiple: bool,
+    capture: Capture,
+    marker: PhantomData<&'a str>,
+}
+
+impl<'a, R: io::Read> Parser<'a, IoRead<R>> {
+    pub fn from_reader(reader: R) -> Parser<'a, IoRead<R>> {
+        Parser::from_reader_with(reader, ParserOptions::new())
+    }
+
+    pub fn from_reader_with(reader: R, options: ParserOptions) -> Parser<'a, IoRead<R>> {
+        Parser::with_options(IoRead::new(reader), options)
+    }
+
+    // The reader, and the bytes read from it but not yet parsed, which come
+    // before whatever it yields next. After a value these start with the
+    // character following it, which the parser has already read to know
+    // the value had ended.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        let (reader, rest) = self.read.into_inner();
+        let mut buf = Vec::with_capacity(rest.len() + 4);
+        if let Some(c) = self.peek {
+            buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        buf.extend_from_slice(&rest);
+        (reader, buf)
+    }
+}
+
+impl<'a, R: Read<'a>> Parser<'a, R> {
+    pub fn new(read: R) -> Parser<'a, R> {
+        Parser::with_options(read, ParserOptions::new())
+    }
+
+    pub fn with_options(read: R, options: ParserOptions) -> Parser<'a, R> {
+        Parser {
+            read,
+            peek: None,
+            position: Position::new(),
+            start: Position::new(),
+            options,
+            stack: Vec::new(),
+            state: State::Start,
+            multiple: false,
+            capture: Capture::Off,
+            marker: PhantomData,
+        }
+    }
+
+    // Accepts any number of top-level values, separated by whitespace or
+    // simply following each other, instead of requiring the input to end
+    // after the first. A number or literal must still be followed by
+    // whitespace, punctuation or the end of the input, so `1true` is an
+    // error rather than two values.
+    pub fn multiple_values(mut self, multiple: bool) -> Parser<'a, R> {
+        self.multiple = multiple;
+        self
+    }
+
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    // Where the token behind the most recent event starts.
+    pub fn event_position(&self) -> Position {
+        self.start
+    }
+
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    // The location of the most recent event: the key or index of every
+    // enclosing member, outermost first.
+    pub fn path(&self) -> Vec<PathSegment> {
+        self.stack
+            .iter()
+            .filter_map(|container| match *container {
+                Container::Object(Some(ref key), _) => Some(PathSegment::Key(key.clone())),
+                Container::Array(Some(index)) => Some(PathSegment::Index(index)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn pointer(&self) -> String {
+        let tokens: Vec<String> = self
+            .path()
+            .into_iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) => key,
+                PathSegment::Index(index) => index.to_string(),
+            })
+            .collect();
+        pointer::to_pointer(&tokens)
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<Event<'a>>, Error> {
+        match self.scan_event() {
+            Ok(event) => {
+                Ok(event)
+            }
+            Err(err) => {
+                self.state = State::Done;
+                Err(err)
+            }
+        }
+    }
+
+    fn scan_event(&mut self) -> Result<Option<Event<'a>>, Error> {
+        loop {
+            if self.state == State::Start {
+                self.peek = self.read_char()?;
+                // With multiple values an empty input is simply no values.
+                self.state = if self.multiple {
+                    State::End
+                } else {
+                    State::Value
+                };
+            }
+            if self.state == State::Done {
+                return Ok(None)
+            }
+            self.ignore_space()?;
+            self.start = self.position;
+
+            match self.state {
+                State::Value => {
+                    return self.match_value().map(Some)
+                }
+                State::ObjectStart if self.peek == Some('}') => {
+                    return self.match_close().map(Some)
+                }
+                State::ObjectStart | State::ObjectKey => {
+                    return self.match_key().map(Some)
+                }
+                State::ObjectComma => {
+                    match self.peek {
+                        Some(',') => {
+                            self.bump()?;
+                            if self.match_trailing_comma('}')? {
+                                return self.match_close().map(Some)
+                            }
+                            self.state = State::ObjectKey;
+                        }
+                        Some('}') => {
+                            return self.match_close().map(Some)
+                        }
+                        _ => {
+                            return Err(self.unexpected("',' or '}'"))
+                        }
+                    }
+                }
+                State::ArrayStart if self.peek == Some(']') => {
+                    return self.match_close().map(Some)
+                }
+                State::ArrayStart => {
+                    if self.options.max_elements == 0 {
+                        return Err(self.limit(ErrorKind::TooManyElements))
+                    }
+                    if let Some(Container::Array(ref mut index)) = self.stack.last_mut() {
+                        *index = Some(0);
+                    }
+                    self.state = State::Value;
+                }
+                State::ArrayComma => {
+                    match self.peek {
+                        Some(',') => {
+                            self.bump()?;
+                            if self.match_trailing_comma(']')? {
+                                return self.match_close().map(Some)
+                            }
+                            let mut count = 0;
+                            if let Some(Container::Array(Some(ref mut index))) = self.stack.last_mut() {
+                                *index += 1;
+                                count = *index;
+                            }
+                            if count >= self.options.max_elements {
+                                // Reported where the element starts.
+                                self.ignore_space()?;
+                                return Err(self.limit(ErrorKind::TooManyElements))
+                            }
+                            self.state = State::Value;
+                        }
+                        Some(']') => {
+                            return self.match_close().map(Some)
+                        }
+                        _ => {
+                            return Err(self.unexpected("',' or ']'"))
+                        }
+                    }
+                }
+                State::End if self.multiple => {
+                    self.state = match self.peek {
+                        Some(_) => State::Value,
+                        None => State::Done,
+                    };
+                }
+                State::End => {
+                    self.match_end()?;
+                    self.state = State::Done;
+                }
+                State::Start | State::Done => {
+                    unreachable!()
+                }
+            }
+        }
+    }
+
+    // Starts recording the input at the next value, so that its exact text
+    // can be recovered with `end_raw` once its last event has been read.
+    pub fn begin_raw(&mut self) {
+        self.capture = Capture::Pending;
+    }
+
+    // Stops recording and returns the text of the value recorded since
+    // `begin_raw`, or an empty string if no value started in between.
+    pub fn end_raw(&mut self) -> Cow<'a, str> {
+        match mem::replace(&mut self.capture, Capture::Off) {
+            Capture::Slice(start) => {
+                match self.read.slice(start, self.position.offset) {
+                    Some(str) => Cow::Borrowed(str),
+                    None => Cow::Borrowed(""),
+                }
+            }
+            Capture::Buffer(buf) => {
+                Cow::Owned(buf)
+            }
+            Capture::Off | Capture::Pending => {
+                Cow::Borrowed("")
+            }
+        }
+    }
+
+    // Abandons the value being parsed, typically after an error, and picks
+    // up again at the start of the next line.
+    pub fn skip_line(&mut self) -> Result<(), Error> {
+        if self.state == State::Start {
+            self.peek = self.read_char()?;
+        }
+        self.stack.clear();
+        self.state = State::End;
+        while let Some(c) = self.peek {
+            self.bump()?;
+            if c == '\n' {
+                break
+            }
+        }
+
+        Ok(())
+    }
+
+    fn match_value(&mut self) -> Result<Event<'a>, Error> {
+        if let Capture::Pending = self.capture {
+            self.capture = if self.read.borrowable() {
+                Capture::Slice(self.position.offset)
+            } else {
+                Capture::Buffer(String::new())
+            };
+        }
+        if let Some('{') | Some('[') = self.peek {
+            if self.stack.len() >= self.options.max_depth {
+                return Err(self.limit(ErrorKind::DepthExceeded))
+            }
+        }
+        let event = match self.peek {
+            Some('{') => {
+                self.bump()?;
+                self.stack.push(Container::Object(None, 0));
+                self.state = State::ObjectStart;
+                return Ok(Event::StartObject)
+            }
+            Some('[') => {
+                self.bump()?;
+                self.stack.push(Container::Array(None));
+                self.state = State::ArrayStart;
+                return Ok(Event::StartArray)
+            }
+            Some('"') => {
+                Event::String(self.match_string()?)
+            }
+            Some('\'') if self.options.single_quotes => {
+                Event::String(self.match_string()?)
+            }
+            Some('t') => {
+                self.match_delimited(Parser::match_true).map(Event::Bool)?
+            }
+            Some('f') => {
+                self.match_delimited(Parser::match_false).map(Event::Bool)?
+            }
+            Some('n') => {
+                self.match_delimited(Parser::match_null)?;
+                Event::Null
+            }
+            Some(c) if self.starts_number(c) => {
+                self.match_delimited(Parser::match_number).map(Event::Number)?
+            }
+            _ => {
+                return Err(self.unexpected("value"))
+            }
+        };
+        self.after_value();
+
+        Ok(event)
+    }
+
+    // Matches a number or literal, which between top-level values has to be
+    // followed by something that could not continue it.
+    fn match_delimited<T>(&mut self, f: fn(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        let value = f(self)?;
+        if !self.multiple || !self.stack.is_empty() {
+            return Ok(value)
+        }
+        match self.peek {
+            None | Some(' ') | Some('\n') | Some('\r') | Some('\t') => {}
+            Some('[') | Some(']') | Some('{') | Some('}') | Some(',') | Some(':') | Some('"') => {}
+            Some('/') if self.options.comments => {}
+            Some('\'') if self.options.single_quotes => {}
+            Some(_) => return Err(self.error(ErrorKind::UnexpectedCharacter, Some("whitespace or end of value"))),
+        }
+
+        Ok(value)
+    }
+
+    fn match_key(&mut self) -> Result<Event<'a>, Error> {
+        let key = match self.peek {
+            Some(c) if self.options.unquoted_keys && is_identifier_start(c) => self.match_identifier()?,
+            _ => self.match_string()?,
+        };
+        self.ignore_space()?;
+        self.match_spec_char(':')?;
+        self.bump()?;
+        let max = self.options.max_members;
+        if let Some(Container::Object(ref mut current, ref mut count)) = self.stack.last_mut() {
+            *current = Some(String::from(&*key));
+            *count += 1;
+            if *count > max {
+                return Err(Error::new(ErrorKind::TooManyMembers, None, None, self.start))
+            }
+        }
+        self.state = State::Value;
+
+        Ok(Event::Key(key))
+    }
+
+    fn match_close(&mut self) -> Result<Event<'a>, Error> {
+        self.bump()?;
+        let event = match self.stack.pop() {
+            Some(Container::Object(..)) => Event::EndObject,
+            _ => Event::EndArray,
+        };
+        self.after_value();
+
+        Ok(event)
+    }
+
+    // After a comma, whether the container ends right away, as a trailing
+    // comma allows. The close is then the token of the next event.
+    fn match_trailing_comma(&mut self, close: char) -> Result<bool, Error> {
+        if !self.options.trailing_commas {
+            return Ok(false)
+        }
+        self.ignore_space()?;
+        self.start = self.position;
+
+        Ok(self.peek == Some(close))
+    }
+
+    fn after_value(&mut self) {
+        self.state = match self.stack.last() {
+            Some(Container::Object(..)) => State::ObjectComma,
+            Some(Container::Array(_)) => State::ArrayComma,
+            None => State::End,
+        };
+    }
+
+    fn read_char(&mut self) -> Result<Option<char>, Error> {
+        match self.read.next() {
+            Ok(Some(c)) if self.options.max_input_len - self.position.offset < c.len_utf8() => {
+                Err(self.limit(ErrorKind::InputTooLong))
+            }
+            Ok(c) => Ok(c),
+            Err(kind) => Err(Error::new(kind, None, None, self.position)),
+        }
+    }
+
+    fn bump(&mut self) -> Result<Option<char>, Error> {
+        if let Some(c) = self.peek {
+            self.position.advance(c);
+            if let Capture::Buffer(ref mut buf) = self.capture {
+                buf.push(c);
+            }
+        }
+        self.peek = self.read_char()?;
+        Ok(self.peek)
+    }
+
+    fn ignore_space(&mut self) -> Result<(), Error> {
+        loop {
+            match self.peek {
+                Some(' ') | Some('\n') | Some('\r') | Some('\t') => {
+                    self.bump()?;
+                }
+                Some('/') if self.options.comments => {
+                    self.match_comment()?;
+                }
+                _ => {
+                    return Ok(())
+                }
+            }
+        }
+    }
+
+    // A `//` comment runs up to the end of the line, which is left as
+    // whitespace; a `/* */` comment must be closed and does not nest.
+    fn match_comment(&mut self) -> Result<(), Error> {
+        self.bump()?;
+        match self.peek {
+            Some('/') => {
+                while let Some(c) = self.bump()? {
+                    if c == '\n' {
+                        break
+                    }
+                }
+            }
+            Some('*') => {
+                let mut star = false;
+                loop {
+                    match self.bump()? {
+                        Some('/') if star => {
+                            self.bump()?;
+                            break
+                        }
+                        Some(c) => {
+                            star = c == '*';
+                        }
+                        None => {
+                            return Err(self.unexpected("'*/'"))
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(self.unexpected("'/' or '*'"))
+            }
+        }
+
+        Ok(())
+    }
+
+    // Strings without escapes are borrowed from the input when the source
+    // allows it; otherwise the unescaped text is collected as it is scanned.
+    fn match_string(&mut self) -> Result<Cow<'a, str>, Error> {
+        let open = self.position;
+        let quote = match self.peek {
+            Some('\'') if self.options.single_quotes => '\'',
+            _ => self.match_spec_char('"')?,
+        };
+        self.bump()?;
+        let start = self.position.offset;
+        let mut str = if self.read.borrowable() {
+            None
+        } else {
+            Some(String::new())
+        };
+
+        loop {
+            // Checked before each character, as an escape or multi-byte
+            // character may carry the string past the limit.
+            let len = self.position.offset - start;
+            if len > self.options.max_string_len || (len == self.options.max_string_len && self.peek != Some(quote)) {
+                break Err(Error::new(ErrorKind::StringTooLong, None, None, open))
+            }
+            match self.peek {
+                Some(c) if c == quote => {
+                    let end = self.position.offset;
+                    self.bump()?;
+                    break Ok(match str {
+                        Some(str) => Cow::Owned(str),
+                        None => Cow::Borrowed(self.read.slice(start, end).unwrap_or_default()),
+                    })
+                }
+                Some('\\') => {
+                    let end = self.position.offset;
+                    let read = &self.read;
+                    let str = str.get_or_insert_with(|| String::from(read.slice(start, end).unwrap_or_default()));
+                    str.push(self.match_escape()?);
+                }
+                Some(c) if c < '\u{20}' => {
+                    break Err(self.error(ErrorKind::UnexpectedCharacter, Some("escaped control character")))
+                }
+                Some(c) => {
+                    if let Some(ref mut str) = str {
+                        str.push(c);
+                    }
+                    self.bump()?;
+                }
+                None => {
+                    break Err(self.unexpected(&format!("{:?}", quote)))
+                }
+            }
+        }
+    }
+
+    // An unquoted key, for `unquoted_keys`. Escapes are not recognized.
+    fn match_identifier(&mut self) -> Result<Cow<'a, str>, Error> {
+        let open = self.position;
+        let start = self.position.offset;
+        let mut str = if self.read.borrowable() {
+            None
+        } else {
+            Some(String::new())
+        };
+        while let Some(c) = self.peek {
+            if !is_identifier_part(c) {
+                break
+            }
+            if self.position.offset - start + c.len_utf8() > self.options.max_string_len {
+                return Err(Error::new(ErrorKind::StringTooLong, None, None, open))
+            }
+            if let Some(ref mut str) = str {
+                str.push(c);
+            }
+            self.bump()?;
+        }
+
+        Ok(match str {
+            Some(str) => Cow::Owned(str),
+            None => Cow::Borrowed(self.read.slice(start, self.position.offset).unwrap_or_default()),
+        })
+    }
+
+    fn match_escape(&mut self) -> Result<char, Error> {
+        let start = self.position;
+        self.match_spec_char('\\')?;
+        self.bump()?;
+        let c = match self.peek {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('\'') if self.options.single_quotes => '\'',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.bump()?;
+                return self.match_unicode_escape(start)
+            }
+            Some(_) => {
+                return Err(self.error(ErrorKind::InvalidEscape, Some("escape character")))
+            }
+            None => {
+                return Err(self.unexpected("escape character"))
+            }
+        };
+        self.bump()?;
+
+        Ok(c)
+    }
+
+    fn match_unicode_escape(&mut self, start: Position) -> Result<char, Error> {
+        let high = self.match_hex_quad()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                let lone = Error::new(
+                    ErrorKind::LoneSurrogate,
+                    Some(String::from("low surrogate escape")),
+                    None,
+                    start,
+                );
+                if self.peek != Some('\\') {
+                    return Err(lone)
+                }
+                self.bump()?;
+                if self.peek != Some('u') {
+                    return Err(lone)
+                }
+                self.bump()?;
+                let low = self.match_hex_quad()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(lone)
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => {
+                return Err(Error::new(ErrorKind::LoneSurrogate, None, None, start))
+            }
+            _ => {
+                high
+            }
+        };
+
+        match char::from_u32(code) {
+            Some(c) => Ok(c),
+            None => Err(Error::new(ErrorKind::InvalidEscape, None, None, start)),
+        }
+    }
+
+    fn match_hex_quad(&mut self) -> Result<u32, Error> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.peek.and_then(|c| c.to_digit(16)) {
+                Some(n) => {
+                    code = code * 16 + n;
+                    self.bump()?;
+                }
+                None => {
+                    return Err(match self.peek {
+                        Some(_) => self.error(ErrorKind::InvalidEscape, Some("hex digit")),
+                        None => self.unexpected("hex digit"),
+                    })
+                }
+            }
+        }
+
+        Ok(code)
+    }
+
+    fn match_number(&mut self) -> Result<Number, Error> {
+        let start = self.position;
+        let mut text = String::new();
+        let mut float = false;
+
+        match self.peek {
+            Some('-') => {
+                text.push('-');
+                self.bump()?;
+            }
+            // Left out of the text, which stays in the JSON grammar.
+            Some('+') if self.options.leading_plus => {
+                self.bump()?;
+            }
+            _ => {}
+        }
+        match self.peek {
+            Some('N') | Some('I') if self.options.nan_infinity => {
+                return self.match_non_finite(text == "-")
+            }
+            Some('0') => {
+                text.push('0');
+                self.bump()?;
+                if let Some('x') | Some('X') = self.peek {
+                    if self.options.hex_numbers {
+                        return self.match_hex(text, start)
+                    }
+                }
+                if self.match_digit().is_ok() {
+                    return Err(self.error(ErrorKind::InvalidNumber, Some("'.', 'e' or end of number")))
+                }
+            }
+            Some('.') if self.options.leading_decimal_point => {
+                text.push('0');
+            }
+            _ => {
+                self.match_digits(&mut text, start)?;
+            }
+        }
+        if self.peek == Some('.') {
+            float = true;
+            text.push('.');
+            self.bump()?;
+            self.match_digits(&mut text, start)?;
+        }
+        if let Some(c @ 'e') | Some(c @ 'E') = self.peek {
+            float = true;
+            text.push(c);
+            self.bump()?;
+            if let Some(c @ '+') | Some(c @ '-') = self.peek {
+                text.push(c);
+                self.bump()?;
+            }
+            self.match_digits(&mut text, start)?;
+        }
+
+        if text.len() > self.options.max_number_len {
+            return Err(Error::new(ErrorKind::NumberTooLong, None, None, start))
+        }
+        match convert_number(&text, float) {
+            Some(number) => Ok(number),
+            None => Err(Error::new(ErrorKind::InvalidNumber, None, None, start)),
+        }
+    }
+
+    fn match_non_finite(&mut self, negative: bool) -> Result<Number, Error> {
+        let n = if self.peek == Some('N') {
+            self.match_keyword("NaN")?;
+            f64::NAN
+        } else {
+            self.match_keyword("Infinity")?;
+            f64::INFINITY
+        };
+
+        Ok(Number::from(if negative { -n } else { n }))
+    }
+
+    // The digits of a hex integer, after its sign and `0`. Values outside
+    // the 64-bit range are rejected rather than rounded to a float.
+    fn match_hex(&mut self, mut text: String, start: Position) -> Result<Number, Error> {
+        let negative = text.starts_with('-');
+        text.push('x');
+        self.bump()?;
+        let mut magnitude: u64 = 0;
+        let mut digits = 0;
+        while let Some(c) = self.peek {
+            let digit = match c.to_digit(16) {
+                Some(digit) => digit,
+                None => break,
+            };
+            if text.len() >= self.options.max_number_len {
+                return Err(Error::new(ErrorKind::NumberTooLong, None, None, start))
+            }
+            magnitude = match magnitude.checked_mul(16).and_then(|m| m.checked_add(u64::from(digit))) {
+                Some(magnitude) => magnitude,
+                None => return Err(Error::new(ErrorKind::InvalidNumber, None, None, start)),
+            };
+            text.push(c);
+            digits += 1;
+            self.bump()?;
+        }
+        if digits == 0 {
+            return Err(match self.peek {
+                Some(_) => self.error(ErrorKind::InvalidNumber, Some("hex digit")),
+                None => self.unexpected("hex digit"),
+            })
+        }
+
+        match (negative, magnitude.cmp(&(1 << 63))) {
+            (false, _) => Ok(Number::from(magnitude)),
+            (true, Ordering::Greater) => Err(Error::new(ErrorKind::InvalidNumber, None, None, start)),
+            // `i64::MIN` has no positive counterpart, so negate after the cast.
+            (true, _) => Ok(Number::from((magnitude as i64).wrapping_neg())),
+        }
+    }
+
+    fn match_digits(&mut self, text: &mut String, start: Position) -> Result<(), Error> {
+        match self.match_digit() {
+            Ok(c) => {
+                text.push(c);
+                self.bump()?;
+            }
+            Err(_) => {
+                return Err(match self.peek {
+                    Some(_) => self.error(ErrorKind::InvalidNumber, Some("digit")),
+                    None => self.unexpected("digit"),
+                })
+            }
+        }
+        while let Ok(c) = self.match_digit() {
+            if text.len() >= self.options.max_number_len {
+                return Err(Error::new(ErrorKind::NumberTooLong, None, None, start))
+            }
+            text.push(c);
+            self.bump()?;
+        }
+
+        Ok(())
+    }
+
+    fn match_end(&mut self) -> Result<(), Error> {
+        match self.peek {
+            Some(_) => {
+                Err(self.error(ErrorKind::TrailingCharacters, Some("end of input")))
+            }
+            None => {
+                Ok(())
+            }
+        }
+    }
+
+    fn match_true(&mut self) -> Result<bool, Error> {
+        self.match_keyword("true")?;
+        Ok(true)
+    }
+
+    fn match_false(&mut self) -> Result<bool, Error> {
+        self.match_keyword("false")?;
+        Ok(false)
+    }
+
+    fn match_null(&mut self) -> Result<(), Error> {
+        self.match_keyword("null")
+    }
+
+    fn match_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        for ic in keyword.chars() {
+            match self.peek {
+                Some(c) if c == ic => {
+                    self.bump()?;
+                }
+                _ => {
+                    return Err(self.unexpected(&format!("`{}`", keyword)))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn match_digit(&mut self) -> Result<char, Error> {
+        match self.peek {
+            Some(c) if c.is_ascii_digit() => {
+                Ok(c)
+            }
+            _ => {
+                Err(self.unexpected("digit"))
+            }
+        }
+    }
+
+    fn match_spec_char(&mut self, ic: char) -> Result<char, Error> {
+        match self.peek {
+            Some(c) if c == ic => {
+                Ok(c)
+            }
+            _ => {
+                Err(self.unexpected(&format!("{:?}", ic)))
+            }
+        }
+    }
+
+    fn starts_number(&self, c: char) -> bool {
+        match c {
+            '-' | '0'..='9' => true,
+            '+' => self.options.leading_plus,
+            '.' => self.options.leading_decimal_point,
+            'N' | 'I' => self.options.nan_infinity,
+            _ => false,
+        }
+    }
+
+    fn error(&self, kind: ErrorKind, expected: Option<&str>) -> Error {
+        Error::new(kind, expected.map(String::from), self.peek, self.position)
+    }
+
+    // Limits are reported where they are exceeded, or for strings and
+    // numbers where they start, without the character found there, which is
+    // not at fault.
+    fn limit(&self, kind: ErrorKind) -> Error {
+        Error::new(kind, None, None, self.position)
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        match self.peek {
+            Some(_) => self.error(ErrorKind::UnexpectedCharacter, Some(expected)),
+            None => self.error(ErrorKind::UnexpectedEof, Some(expected)),
+        }
+    }
+}
+
+// Reads `str` as a single JSON number, with nothing around it, not even
+// whitespace.
+pub(crate) fn parse_number(str: &str) -> Result<Number, Error> {
+    let mut parser = Parser::new(StrRead::new(str));
+    parser.peek = parser.read_char()?;
+    let number = match parser.peek {
+        Some(c) if parser.starts_number(c) => parser.match_number()?,
+        _ => return Err(parser.unexpected("number")),
+    };
+    parser.match_end()?;
+
+    Ok(number)
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// Converts the text of a number already checked against the grammar.
+// Integers that fit are kept exact; everything else becomes the nearest
+// `f64`, and only values out of `f64` range are rejected.
+pub(crate) fn convert_number(text: &str, float: bool) -> Option<Number> {
+    let num = if float || text == "-0" {
+        None
+    } else if text.starts_with('-') {
+        text.parse().ok().map(Num::NegInt)
+    } else {
+        text.parse().ok().map(Num::PosInt)
+    };
+    let num = match num {
+        Some(num) => num,
+        None => {
+            match text.parse::<f64>() {
+                Ok(f) if f.is_finite() => Num::Float(f),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(Number::parsed(num, text))
+}
+
+impl<'a, R: Read<'a>> Iterator for Parser<'a, R> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Result<Event<'a>, Error>> {
+        self.next_event().transpose()
+    }
+}
@@ -0,0 +1,704 @@
+use std::mem;
+
+use super::Duplicate;
+use super::Error;
+use super::ErrorKind;
+use super::Members;
+use super::ParserOptions;
+use super::Position;
+use super::parser::convert_number;
+use super::super::value::Value;
+
+pub enum Status {
+    NeedMore,
+    Complete(Value),
+}
+
+enum Escape {
+    None,
+    Backslash,
+    Unicode { code: u32, count: u8, high: Option<u32> },
+    // A high surrogate was read and must be followed by `\u`.
+    SurrogateBackslash(u32),
+    SurrogateU(u32),
+}
+
+// A multi-byte UTF-8 sequence part way through a string. Sequences are
+// checked as their bytes arrive, so an error points at the sequence in the
+// input rather than somewhere in the unescaped text.
+#[derive(Clone, Copy)]
+struct Utf8 {
+    start: Position,
+    remaining: u8,
+    // The range the next byte must fall in. Only the first continuation
+    // byte is ever narrower than 0x80..=0xbf.
+    lower: u8,
+    upper: u8,
+}
+
+enum NumberState {
+    Minus,
+    Zero,
+    Int,
+    Dot,
+    Fraction,
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+}
+
+// The token being scanned when a chunk ends, kept so the next chunk carries
+// on where this one stopped.
+enum Lex {
+    None,
+    String {
+        buf: Vec<u8>,
+        escape: Escape,
+        escape_start: Position,
+        utf8: Option<Utf8>,
+        key: bool,
+        start: Position,
+    },
+    Number {
+        text: String,
+        state: NumberState,
+        start: Position,
+    },
+    // A top-level literal stays here once fully matched, until the byte
+    // after it shows it is not run together with the next value.
+    Literal {
+        word: &'static str,
+        matched: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Expect {
+    Value,
+    ValueOrArrayEnd,
+    ObjectKeyOrEnd,
+    ObjectKey,
+    Colon,
+    CommaOrObjectEnd,
+    CommaOrArrayEnd,
+}
+
+impl Expect {
+    fn description(self) -> &'static str {
+        match self {
+            Expect::Value | Expect::ValueOrArrayEnd => "value",
+            Expect::ObjectKeyOrEnd | Expect::ObjectKey => "'\"'",
+            Expect::Colon => "':'",
+            Expect::CommaOrObjectEnd => "',' or '}'",
+            Expect::CommaOrArrayEnd => "',' or ']'",
+        }
+    }
+}
+
+// An unfinished container. Objects also count the members read, repeated
+// keys included.
+enum Frame {
+    Array(Vec<Value>),
+    Object(Members, Option<(String, Position)>, usize),
+}
+
+enum Step {
+    Continue,
+    // A top-level value finished; `consumed` is false when the byte that
+    // ended it (say, the `{` after `123`) belongs to the next value.
+    Complete(Value, bool),
+}
+
+// A push parser: bytes are fed in whatever chunks they arrive in, and each
+// call reports whether a complete top-level value is available yet. Every
+// byte is scanned exactly once; partial tokens, including strings, numbers
+// and `\u` escapes split across chunks, are kept between calls.
+//
+// Top-level values may follow each other in the input, a number or literal
+// only with whitespace or punctuation after it. When a chunk holds more than
+// one, the bytes after the first are kept and scanned by the next call to
+// `feed` (an empty chunk is fine). They are read from an offset, and the
+// buffer is compacted only when a new chunk is appended, so a chunk of many
+// small values costs time linear in its length.
+pub struct PushParser {
+    options: ParserOptions,
+    position: Position,
+    pending: Vec<u8>,
+    // How much of `pending` has been scanned.
+    consumed: usize,
+    lex: Lex,
+    expect: Expect,
+    stack: Vec<Frame>,
+    duplicates: Vec<Duplicate>,
+    failed: Option<Error>,
+}
+
+impl PushParser {
+    pub fn new() -> PushParser {
+        PushParser::with_options(ParserOptions::new())
+    }
+
+    pub fn with_options(options: ParserOptions) -> PushParser {
+        PushParser {
+            options,
+            position: Position::new(),
+            pending: Vec::new(),
+            consumed: 0,
+            lex: Lex::None,
+            expect: Expect::Value,
+            stack: Vec::new(),
+            duplicates: Vec::new(),
+            failed: None,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Status, Error> {
+        if let Some(ref err) = self.failed {
+            return Err(err.clone())
+        }
+        let result = if self.consumed == self.pending.len() {
+            self.pending.clear();
+            self.consumed = 0;
+            self.process(chunk).map(|(status, used)| {
+                self.pending.extend_from_slice(&chunk[used..]);
+                status
+            })
+        } else {
+            if !chunk.is_empty() {
+                self.pending.drain(..self.consumed);
+                self.consumed = 0;
+                self.pending.extend_from_slice(chunk);
+            }
+            let pending = mem::take(&mut self.pending);
+            let result = self.process(&pending[self.consumed..]);
+            self.pending = pending;
+            result.map(|(status, used)| {
+                self.consumed += used;
+                status
+            })
+        };
+        if let Err(ref err) = result {
+            self.failed = Some(err.clone());
+        }
+
+        result
+    }
+
+    // Signals the end of the input. A top-level number or literal can only be
+    // known to be complete here; anything else left unfinished is an error. Values
+    // still pending from an earlier chunk are returned first, so call this
+    // until it yields `None`.
+    pub fn finish(&mut self) -> Result<Option<Value>, Error> {
+        if let Status::Complete(value) = self.feed(&[])? {
+            return Ok(Some(value))
+        }
+        let result = match mem::replace(&mut self.lex, Lex::None) {
+            Lex::Number { text, state, start } if self.stack.is_empty() => {
+                self.match_number(text, state, start).map(Some)
+            }
+            Lex::Literal { word, matched } if matched == word.len() => {
+                Ok(Some(literal_value(word)))
+            }
+            Lex::None if self.stack.is_empty() => {
+                Ok(None)
+            }
+            Lex::None => {
+                let expected = String::from(self.expect.description());
+                Err(Error::new(ErrorKind::UnexpectedEof, Some(expected), None, self.position))
+            }
+            _ => {
+                Err(Error::new(ErrorKind::UnexpectedEof, None, None, self.position))
+            }
+        };
+        if let Err(ref err) = result {
+            self.failed = Some(err.clone());
+        }
+
+        result
+    }
+
+    // Scans `input` up to the end of the next top-level value, returning
+    // how many bytes that took.
+    fn process(&mut self, input: &[u8]) -> Result<(Status, usize), Error> {
+        for (index, &byte) in input.iter().enumerate() {
+            if let Step::Complete(value, consumed) = self.push_byte(byte)? {
+                let used = if consumed { index + 1 } else { index };
+                return Ok((Status::Complete(value), used))
+            }
+        }
+
+        Ok((Status::NeedMore, input.len()))
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Step, Error> {
+        if self.position.offset >= self.options.max_input_len {
+            return Err(self.limit(ErrorKind::InputTooLong))
+        }
+        let step = match self.lex {
+            Lex::None => {
+                self.structural_byte(byte)?
+            }
+            Lex::String { .. } => {
+                self.string_byte(byte)?
+            }
+            Lex::Literal { word, matched } if matched == word.len() => {
+                self.lex = Lex::None;
+                self.match_delimiter(byte)?;
+                let value = literal_value(word);
+                return Ok(Step::Complete(value, false))
+            }
+            Lex::Literal { .. } => {
+                self.literal_byte(byte)?
+            }
+            Lex::Number { .. } => {
+                match self.number_byte(byte)? {
+                    Some(step) => {
+                        step
+                    }
+                    None => {
+                        // The number ended just before this byte.
+                        if self.stack.is_empty() {
+                            self.match_delimiter(byte)?;
+                        }
+                        let value = match mem::replace(&mut self.lex, Lex::None) {
+                            Lex::Number { text, state, start } => self.match_number(text, state, start)?,
+                            _ => unreachable!(),
+                        };
+                        match self.complete_value(value)? {
+                            Some(value) => return Ok(Step::Complete(value, false)),
+                            None => self.structural_byte(byte)?,
+                        }
+                    }
+                }
+            }
+        };
+        self.position.advance_byte(byte);
+
+        Ok(step)
+    }
+
+    fn structural_byte(&mut self, byte: u8) -> Result<Step, Error> {
+        if let b' ' | b'\n' | b'\r' | b'\t' = byte {
+            return Ok(Step::Continue)
+        }
+
+        match (self.expect, byte) {
+            (Expect::ValueOrArrayEnd, b']') | (Expect::CommaOrArrayEnd, b']') => {
+                match self.stack.pop() {
+                    Some(Frame::Array(list)) => self.complete_token(Value::Array(list)),
+                    _ => unreachable!(),
+                }
+            }
+            (Expect::ObjectKeyOrEnd, b'}') | (Expect::CommaOrObjectEnd, b'}') => {
+                match self.stack.pop() {
+                    Some(Frame::Object(members, ..)) => self.complete_token(Value::Object(members.into_map())),
+                    _ => unreachable!(),
+                }
+            }
+            (Expect::Value, _) | (Expect::ValueOrArrayEnd, _) => {
+                self.start_value(byte)
+            }
+            (Expect::ObjectKeyOrEnd, b'"') | (Expect::ObjectKey, b'"') => {
+                self.start_string(true);
+                Ok(Step::Continue)
+            }
+            (Expect::Colon, b':') => {
+                self.expect = Expect::Value;
+                Ok(Step::Continue)
+            }
+            (Expect::CommaOrObjectEnd, b',') => {
+                self.expect = Expect::ObjectKey;
+                Ok(Step::Continue)
+            }
+            (Expect::CommaOrArrayEnd, b',') => {
+                self.expect = Expect::Value;
+                Ok(Step::Continue)
+            }
+            (expect, _) => {
+                Err(self.error(ErrorKind::UnexpectedCharacter, Some(expect.description()), byte))
+            }
+        }
+    }
+
+    fn start_value(&mut self, byte: u8) -> Result<Step, Error> {
+        if (byte == b'{' || byte == b'[') && self.stack.len() >= self.options.max_depth {
+            return Err(self.limit(ErrorKind::DepthExceeded))
+        }
+        if let Some(Frame::Array(ref list)) = self.stack.last() {
+            if list.len() >= self.options.max_elements {
+                return Err(self.limit(ErrorKind::TooManyElements))
+            }
+        }
+        match byte {
+            b'{' => {
+                self.stack.push(Frame::Object(Members::new(), None, 0));
+                self.expect = Expect::ObjectKeyOrEnd;
+            }
+            b'[' => {
+                self.stack.push(Frame::Array(Vec::new()));
+                self.expect = Expect::ValueOrArrayEnd;
+            }
+            b'"' => {
+                self.start_string(false);
+            }
+            b't' => {
+                self.lex = Lex::Literal { word: "true", matched: 1 };
+            }
+            b'f' => {
+                self.lex = Lex::Literal { word: "false", matched: 1 };
+            }
+            b'n' => {
+                self.lex = Lex::Literal { word: "null", matched: 1 };
+            }
+            b'-' => {
+                self.lex = Lex::Number { text: String::from("-"), state: NumberState::Minus, start: self.position };
+            }
+            b'0' => {
+                self.lex = Lex::Number { text: String::from("0"), state: NumberState::Zero, start: self.position };
+            }
+            b'1'..=b'9' => {
+                self.lex = Lex::Number { text: (byte as char).to_string(), state: NumberState::Int, start: self.position };
+            }
+            _ => {
+                return Err(self.error(ErrorKind::UnexpectedCharacter, Some("value"), byte))
+            }
+        }
+
+        Ok(Step::Continue)
+    }
+
+    fn start_string(&mut self, key: bool) {
+        self.lex = Lex::String {
+            buf: Vec::new(),
+            escape: Escape::None,
+            escape_start: self.position,
+            utf8: None,
+            key,
+            start: self.position,
+        };
+    }
+
+    fn string_byte(&mut self, byte: u8) -> Result<Step, Error> {
+        let position = self.position;
+        let max = self.options.max_string_len;
+        let (buf, escape, escape_start, utf8, start) = match self.lex {
+            Lex::String { ref mut buf, ref mut escape, ref mut escape_start, ref mut utf8, start, .. } => {
+                (buf, escape, escape_start, utf8, start)
+            }
+            _ => unreachable!(),
+        };
+        // `start` is the opening quote, so this counts the byte at hand.
+        let closing = byte == b'"' && matches!(*escape, Escape::None);
+        if !closing && position.offset - start.offset > max {
+            return Err(Error::new(ErrorKind::StringTooLong, None, None, start))
+        }
+        let lone = |escape_start: Position| {
+            Error::new(ErrorKind::LoneSurrogate, Some(String::from("low surrogate escape")), None, escape_start)
+        };
+        let invalid = |start: Position| Error::new(ErrorKind::InvalidUtf8, None, None, start);
+
+        if let Some(ref mut sequence) = *utf8 {
+            if !(sequence.lower..=sequence.upper).contains(&byte) {
+                return Err(invalid(sequence.start))
+            }
+            buf.push(byte);
+            sequence.remaining -= 1;
+            sequence.lower = 0x80;
+            sequence.upper = 0xbf;
+            if sequence.remaining == 0 {
+                *utf8 = None;
+            }
+            return Ok(Step::Continue)
+        }
+
+        match *escape {
+            Escape::None => {
+                match byte {
+                    b'"' => {
+                        return self.end_string()
+                    }
+                    b'\\' => {
+                        *escape = Escape::Backslash;
+                        *escape_start = position;
+                    }
+                    0x00..=0x1f => {
+                        return Err(self.error(ErrorKind::UnexpectedCharacter, Some("escaped control character"), byte))
+                    }
+                    0x20..=0x7f => {
+                        buf.push(byte);
+                    }
+                    _ => {
+                        // The lead byte gives the length, and for a few the
+                        // range of the next byte, ruling out overlong forms,
+                        // surrogates and code points past U+10FFFF.
+                        let (remaining, lower, upper) = match byte {
+                            0xc2..=0xdf => (1, 0x80, 0xbf),
+                            0xe0 => (2, 0xa0, 0xbf),
+                            0xed => (2, 0x80, 0x9f),
+                            0xe1..=0xef => (2, 0x80, 0xbf),
+                            0xf0 => (3, 0x90, 0xbf),
+                            0xf1..=0xf3 => (3, 0x80, 0xbf),
+                            0xf4 => (3, 0x80, 0x8f),
+                            _ => return Err(invalid(position)),
+                        };
+                        buf.push(byte);
+                        *utf8 = Some(Utf8 {
+                            start: position,
+                            remaining,
+                            lower,
+                            upper,
+                        });
+                    }
+                }
+            }
+            Escape::Backslash => {
+                let c = match byte {
+                    b'"' => '"',
+                    b'\\' => '\\',
+                    b'/' => '/',
+                    b'b' => '\u{8}',
+                    b'f' => '\u{c}',
+                    b'n' => '\n',
+                    b'r' => '\r',
+                    b't' => '\t',
+                    b'u' => {
+                        *escape = Escape::Unicode { code: 0, count: 0, high: None };
+                        return Ok(Step::Continue)
+                    }
+                    _ => {
+                        return Err(self.error(ErrorKind::InvalidEscape, Some("escape character"), byte))
+                    }
+                };
+                push_char(buf, c);
+                *escape = Escape::None;
+            }
+            Escape::Unicode { ref mut code, ref mut count, high } => {
+                match (byte as char).to_digit(16) {
+                    Some(n) => {
+                        *code = *code * 16 + n;
+                        *count += 1;
+                    }
+                    None => {
+                        return Err(self.error(ErrorKind::InvalidEscape, Some("hex digit"), byte))
+                    }
+                }
+                if *count == 4 {
+                    let code = *code;
+                    let c = match (high, code) {
+                        (Some(high), 0xDC00..=0xDFFF) => {
+                            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00))
+                        }
+                        (Some(_), _) | (None, 0xDC00..=0xDFFF) => {
+                            return Err(lone(*escape_start))
+                        }
+                        (None, 0xD800..=0xDBFF) => {
+                            *escape = Escape::SurrogateBackslash(code);
+                            return Ok(Step::Continue)
+                        }
+                        (None, _) => {
+                            char::from_u32(code)
+                        }
+                    };
+                    match c {
+                        Some(c) => push_char(buf, c),
+                        None => return Err(Error::new(ErrorKind::InvalidEscape, None, None, *escape_start)),
+                    }
+                    *escape = Escape::None;
+                }
+            }
+            Escape::SurrogateBackslash(high) => {
+                if byte != b'\\' {
+                    return Err(lone(*escape_start))
+                }
+                *escape = Escape::SurrogateU(high);
+            }
+            Escape::SurrogateU(high) => {
+                if byte != b'u' {
+                    return Err(lone(*escape_start))
+                }
+                *escape = Escape::Unicode { code: 0, count: 0, high: Some(high) };
+            }
+        }
+
+        Ok(Step::Continue)
+    }
+
+    fn end_string(&mut self) -> Result<Step, Error> {
+        let (buf, key, start) = match mem::replace(&mut self.lex, Lex::None) {
+            Lex::String { buf, key, start, .. } => (buf, key, start),
+            _ => unreachable!(),
+        };
+        let str = String::from_utf8(buf).expect("string bytes are checked as they are read");
+
+        if key {
+            let max = self.options.max_members;
+            if let Some(Frame::Object(_, ref mut current, ref mut count)) = self.stack.last_mut() {
+                *current = Some((str, start));
+                *count += 1;
+                if *count > max {
+                    return Err(Error::new(ErrorKind::TooManyMembers, None, None, start))
+                }
+            }
+            self.expect = Expect::Colon;
+            Ok(Step::Continue)
+        } else {
+            self.complete_token(Value::String(str))
+        }
+    }
+
+    fn literal_byte(&mut self, byte: u8) -> Result<Step, Error> {
+        let (word, matched) = match self.lex {
+            Lex::Literal { word, ref mut matched } => {
+                if word.as_bytes()[*matched] != byte {
+                    let expected = format!("`{}`", word);
+                    return Err(self.error(ErrorKind::UnexpectedCharacter, Some(&expected), byte))
+                }
+                *matched += 1;
+                (word, *matched)
+            }
+            _ => unreachable!(),
+        };
+        if matched < word.len() || self.stack.is_empty() {
+            return Ok(Step::Continue)
+        }
+        self.lex = Lex::None;
+
+        self.complete_token(literal_value(word))
+    }
+
+    // Between top-level values, a number or literal has to be followed by
+    // something that could not continue it, as `Parser` requires.
+    fn match_delimiter(&self, byte: u8) -> Result<(), Error> {
+        match byte {
+            b' ' | b'\n' | b'\r' | b'\t' | b'[' | b']' | b'{' | b'}' | b',' | b':' | b'"' => {
+                Ok(())
+            }
+            _ => {
+                Err(self.error(ErrorKind::UnexpectedCharacter, Some("whitespace or end of value"), byte))
+            }
+        }
+    }
+
+    // Returns `None` when `byte` cannot continue the number, which then ends.
+    fn number_byte(&mut self, byte: u8) -> Result<Option<Step>, Error> {
+        let (text, state, start) = match self.lex {
+            Lex::Number { ref mut text, ref mut state, start } => (text, state, start),
+            _ => unreachable!(),
+        };
+        let next = match (&*state, byte) {
+            (NumberState::Minus, b'0') => NumberState::Zero,
+            (NumberState::Minus, b'1'..=b'9') => NumberState::Int,
+            (NumberState::Zero, b'0'..=b'9') => {
+                return Err(self.error(ErrorKind::InvalidNumber, Some("'.', 'e' or end of number"), byte))
+            }
+            (NumberState::Int, b'0'..=b'9') => NumberState::Int,
+            (NumberState::Zero, b'.') | (NumberState::Int, b'.') => NumberState::Dot,
+            (NumberState::Dot, b'0'..=b'9') | (NumberState::Fraction, b'0'..=b'9') => NumberState::Fraction,
+            (NumberState::Zero, b'e') | (NumberState::Zero, b'E')
+            | (NumberState::Int, b'e') | (NumberState::Int, b'E')
+            | (NumberState::Fraction, b'e') | (NumberState::Fraction, b'E') => NumberState::Exponent,
+            (NumberState::Exponent, b'+') | (NumberState::Exponent, b'-') => NumberState::ExponentSign,
+            (NumberState::Exponent, b'0'..=b'9')
+            | (NumberState::ExponentSign, b'0'..=b'9')
+            | (NumberState::ExponentDigits, b'0'..=b'9') => NumberState::ExponentDigits,
+            (NumberState::Minus, _) | (NumberState::Dot, _) | (NumberState::Exponent, _) | (NumberState::ExponentSign, _) => {
+                return Err(self.error(ErrorKind::InvalidNumber, Some("digit"), byte))
+            }
+            _ => {
+                return Ok(None)
+            }
+        };
+        if text.len() >= self.options.max_number_len {
+            return Err(Error::new(ErrorKind::NumberTooLong, None, None, start))
+        }
+        text.push(byte as char);
+        *state = next;
+
+        Ok(Some(Step::Continue))
+    }
+
+    fn match_number(&mut self, text: String, state: NumberState, start: Position) -> Result<Value, Error> {
+        let float = match state {
+            NumberState::Zero | NumberState::Int => false,
+            NumberState::Fraction | NumberState::ExponentDigits => true,
+            _ => return Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("digit")), None, self.position)),
+        };
+        if text.len() > self.options.max_number_len {
+            return Err(Error::new(ErrorKind::NumberTooLong, None, None, start))
+        }
+        match convert_number(&text, float) {
+            Some(number) => Ok(Value::Number(number)),
+            None => Err(Error::new(ErrorKind::InvalidNumber, None, None, start)),
+        }
+    }
+
+    // Completes a value whose last byte is the one being scanned.
+    fn complete_token(&mut self, value: Value) -> Result<Step, Error> {
+        match self.complete_value(value)? {
+            Some(value) => Ok(Step::Complete(value, true)),
+            None => Ok(Step::Continue),
+        }
+    }
+
+    // Hands a finished value to its parent, or returns it when it is the
+    // top-level value.
+    fn complete_value(&mut self, value: Value) -> Result<Option<Value>, Error> {
+        match self.stack.last_mut() {
+            Some(Frame::Array(ref mut list)) => {
+                list.push(value);
+                self.expect = Expect::CommaOrArrayEnd;
+                Ok(None)
+            }
+            Some(Frame::Object(ref mut members, ref mut current, _)) => {
+                let (key, position) = current.take().expect("object member without key");
+                members.insert(self.options.duplicate_keys, &mut self.duplicates, key, value, position)?;
+                self.expect = Expect::CommaOrObjectEnd;
+                Ok(None)
+            }
+            None => {
+                self.expect = Expect::Value;
+                Ok(Some(value))
+            }
+        }
+    }
+
+    fn limit(&self, kind: ErrorKind) -> Error {
+        Error::new(kind, None, None, self.position)
+    }
+
+    fn error(&self, kind: ErrorKind, expected: Option<&str>, byte: u8) -> Error {
+        let found = if byte.is_ascii() {
+            Some(byte as char)
+        } else {
+            None
+        };
+        Error::new(kind, expected.map(String::from), found, self.position)
+    }
+}
+
+impl Default for PushParser {
+    fn default() -> PushParser {
+        PushParser::new()
+    }
+}
+
+fn literal_value(word: &str) -> Value {
+    match word {
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        _ => Value::Null,
+    }
+}
+
+fn push_char(buf: &mut Vec<u8>, c: char) {
+    let mut bytes = [0; 4];
+    buf.extend_from_slice(c.encode_utf8(&mut bytes).as_bytes());
+}
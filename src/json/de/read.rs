@@ -0,0 +1,131 @@
+use std::io;
+use std::str;
+use std::str::Chars;
+
+use super::ErrorKind;
+
+// A source of characters for the parser. Sources backed by the whole input
+// in memory can also hand out slices of it, which lets strings without
+// escapes be borrowed instead of copied.
+pub trait Read<'a> {
+    fn next(&mut self) -> Result<Option<char>, ErrorKind>;
+
+    fn slice(&self, _start: usize, _end: usize) -> Option<&'a str> {
+        None
+    }
+
+    fn borrowable(&self) -> bool {
+        false
+    }
+}
+
+pub struct StrRead<'a> {
+    str: &'a str,
+    chars: Chars<'a>,
+}
+
+impl<'a> StrRead<'a> {
+    pub fn new(str: &'a str) -> StrRead<'a> {
+        StrRead {
+            str,
+            chars: str.chars(),
+        }
+    }
+}
+
+impl<'a> Read<'a> for StrRead<'a> {
+    fn next(&mut self) -> Result<Option<char>, ErrorKind> {
+        Ok(self.chars.next())
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Option<&'a str> {
+        self.str.get(start..end)
+    }
+
+    fn borrowable(&self) -> bool {
+        true
+    }
+}
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// Decodes UTF-8 from an `io::Read` a buffer at a time, so only the buffer
+// and the value being parsed are ever held in memory.
+pub struct IoRead<R> {
+    reader: R,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+}
+
+impl<R: io::Read> IoRead<R> {
+    pub fn new(reader: R) -> IoRead<R> {
+        IoRead {
+            reader,
+            buf: vec![0; BUFFER_SIZE],
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    // The reader, and the bytes already read from it but not yet decoded,
+    // which come before whatever the reader yields next. Once a `Parser`
+    // owns this, use `Parser::into_inner`, which also returns the character
+    // the parser has read ahead.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        let rest = self.buf[self.pos..self.len].to_vec();
+        (self.reader, rest)
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, ErrorKind> {
+        while self.pos == self.len {
+            match self.reader.read(&mut self.buf) {
+                Ok(0) => {
+                    return Ok(None)
+                }
+                Ok(len) => {
+                    self.pos = 0;
+                    self.len = len;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {
+                    continue
+                }
+                Err(err) => {
+                    return Err(ErrorKind::Io(err.kind()))
+                }
+            }
+        }
+        let byte = self.buf[self.pos];
+        self.pos += 1;
+
+        Ok(Some(byte))
+    }
+}
+
+impl<'a, R: io::Read> Read<'a> for IoRead<R> {
+    fn next(&mut self) -> Result<Option<char>, ErrorKind> {
+        let first = match self.next_byte()? {
+            Some(byte) => byte,
+            None => return Ok(None),
+        };
+        let width = match first {
+            0x00..=0x7f => return Ok(Some(first as char)),
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => return Err(ErrorKind::InvalidUtf8),
+        };
+        let mut bytes = [first, 0, 0, 0];
+        for byte in bytes.iter_mut().take(width).skip(1) {
+            match self.next_byte()? {
+                Some(b) => *byte = b,
+                None => return Err(ErrorKind::InvalidUtf8),
+            }
+        }
+
+        match str::from_utf8(&bytes[..width]) {
+            Ok(str) => Ok(str.chars().next()),
+            Err(_) => Err(ErrorKind::InvalidUtf8),
+        }
+    }
+}
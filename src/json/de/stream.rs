@@ -0,0 +1,114 @@
+use std::io;
+
+use super::De;
+use super::Error;
+use super::ErrorKind;
+use super::IoRead;
+use super::Parser;
+use super::ParserOptions;
+use super::Position;
+use super::Read;
+use super::StrRead;
+use super::super::value::Value;
+
+// Reads a sequence of top-level values, as found in newline-delimited JSON
+// (JSON Lines) or in values simply written one after another (`{}{}[]`).
+// Blank lines and other whitespace between values are ignored, but a number
+// or literal needs some after it before the next value, as in `1 true`.
+//
+// By default iteration stops after the first error. With `skip_invalid` the
+// rest of the line holding a bad record is discarded and reading carries on
+// with the next line; the error is still yielded so it can be reported. A
+// record cut short by a newline is only noticed on the line after it, which
+// is then skipped as well.
+pub struct StreamDeserializer<'a, R> {
+    de: De<'a, R>,
+    skip_invalid: bool,
+    done: bool,
+}
+
+impl<'a> StreamDeserializer<'a, StrRead<'a>> {
+    pub fn new(str: &'a str) -> StreamDeserializer<'a, StrRead<'a>> {
+        StreamDeserializer::with_options(str, ParserOptions::new())
+    }
+
+    pub fn with_options(str: &'a str, options: ParserOptions) -> StreamDeserializer<'a, StrRead<'a>> {
+        StreamDeserializer::from_parser(Parser::with_options(StrRead::new(str), options))
+    }
+}
+
+impl<'a, R: io::Read> StreamDeserializer<'a, IoRead<R>> {
+    pub fn from_reader(reader: R) -> StreamDeserializer<'a, IoRead<R>> {
+        StreamDeserializer::from_reader_with(reader, ParserOptions::new())
+    }
+
+    pub fn from_reader_with(reader: R, options: ParserOptions) -> StreamDeserializer<'a, IoRead<R>> {
+        StreamDeserializer::from_parser(Parser::from_reader_with(reader, options))
+    }
+}
+
+impl<'a, R: Read<'a>> StreamDeserializer<'a, R> {
+    pub fn from_parser(parser: Parser<'a, R>) -> StreamDeserializer<'a, R> {
+        StreamDeserializer {
+            de: De::from_parser(parser.multiple_values(true)),
+            skip_invalid: false,
+            done: false,
+        }
+    }
+
+    pub fn skip_invalid(mut self, skip_invalid: bool) -> StreamDeserializer<'a, R> {
+        self.skip_invalid = skip_invalid;
+        self
+    }
+
+    pub fn de(&self) -> &De<'a, R> {
+        &self.de
+    }
+
+    pub fn offset(&self) -> usize {
+        self.de.offset()
+    }
+
+    pub fn position(&self) -> Position {
+        self.de.position()
+    }
+
+    fn match_record(&mut self) -> Result<Option<Value>, Error> {
+        match self.de.parser.next_event()? {
+            Some(event) => self.de.match_event(event).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    // Moves past a bad record. Errors from the reader itself cannot be
+    // skipped and end the stream.
+    fn recover(&mut self, err: &Error) {
+        let io = matches!(err.kind(), ErrorKind::Io(_));
+        if !self.skip_invalid || io || self.de.parser.skip_line().is_err() {
+            self.done = true;
+        }
+    }
+}
+
+impl<'a, R: Read<'a>> Iterator for StreamDeserializer<'a, R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Result<Value, Error>> {
+        if self.done {
+            return None
+        }
+        match self.match_record() {
+            Ok(Some(value)) => {
+                Some(Ok(value))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.recover(&err);
+                Some(Err(err))
+            }
+        }
+    }
+}
@@ -1,5 +1,22 @@
 pub use super::value::Value;
 
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Element {
     value: Value
-}
\ No newline at end of file
+}
+
+impl Element {
+    pub fn new(value: Value) -> Element {
+        Element {
+            value
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
@@ -0,0 +1,188 @@
+// `json!` builds a `Value` from JSON-like syntax, with any Rust expression
+// allowed where a value or key is expected:
+//
+//     let value = json!({
+//         "name": name,
+//         "phones": [home, work],
+//         "active": true,
+//     });
+//
+// Values are converted with `Value::from` and keys with `String::from`, so
+// anything with the matching `From` impl can be interpolated. An expression
+// used as a key must be wrapped in parentheses unless it is a single token.
+// Trailing commas are accepted. Later duplicate keys replace earlier ones.
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+// The muncher behind `json!`. Arrays accumulate their elements as
+// expressions in `[...]`; objects insert each member into a map as soon as
+// its key and value have been split off, keeping a copy of the remaining
+// tokens to report a better error on a missing value.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    // Array elements, done.
+    (@array [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+
+    (@array [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+
+    // Array elements that are literals or nested containers.
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(null)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(true)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(false)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!({$($map)*})] $($rest)*)
+    };
+
+    // Array elements that are expressions, followed by a comma or last.
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($next),] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($last)])
+    };
+
+    // The comma after an element that was a literal or container.
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Object members, done.
+    (@object $map:ident () () ()) => {};
+
+    // Insert a member, followed by a comma or last.
+    (@object $map:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $map.insert(::std::string::String::from($($key)+), $value);
+        $crate::json_internal!(@object $map () ($($rest)*) ($($rest)*));
+    };
+
+    (@object $map:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $map.insert(::std::string::String::from($($key)+), $value);
+    };
+
+    // Member values that are literals or nested containers.
+    (@object $map:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+
+    (@object $map:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+
+    (@object $map:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+
+    (@object $map:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+
+    (@object $map:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!({$($inner)*})) $($rest)*);
+    };
+
+    // Member values that are expressions, followed by a comma or last.
+    (@object $map:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+
+    (@object $map:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_internal!(@object $map [$($key)+] ($crate::json_internal!($value)));
+    };
+
+    // A key with no value: point the error at the end of the input.
+    (@object $map:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::json_internal!();
+    };
+
+    // A key with no colon: point the error at the last token of the key.
+    (@object $map:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::json_internal!();
+    };
+
+    // A colon with no key: point the error at the colon.
+    (@object $map:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($colon);
+    };
+
+    // A comma right after a key: point the error at the comma.
+    (@object $map:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($comma);
+    };
+
+    // A parenthesized key is taken whole.
+    (@object $map:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // Otherwise add the next token to the key.
+    (@object $map:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $map ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    // Entry points.
+    (null) => {
+        $crate::json::Value::Null
+    };
+
+    (true) => {
+        $crate::json::Value::Boolean(true)
+    };
+
+    (false) => {
+        $crate::json::Value::Boolean(false)
+    };
+
+    ([]) => {
+        $crate::json::Value::Array(vec![])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::json::Value::Array($crate::json_internal!(@array [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::json::Value::Object($crate::json::Map::new())
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::json::Value::Object({
+            let mut map = $crate::json::Map::new();
+            $crate::json_internal!(@object map () ($($tt)+) ($($tt)+));
+            map
+        })
+    };
+
+    ($other:expr) => {
+        $crate::json::Value::from($other)
+    };
+}
+
+// Matches nothing, so using it reports the given token as unexpected.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_unexpected {
+    () => {};
+}
@@ -1,3 +1,30 @@
+mod macros;
 pub mod element;
 pub mod value;
-pub mod de;
\ No newline at end of file
+pub mod de;
+pub mod ser;
+pub mod patch;
+pub mod convert;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+pub use value::Value;
+pub use value::Map;
+pub use value::BorrowedValue;
+pub use value::RawValue;
+pub use de::Error;
+pub use de::from_str;
+pub use de::from_slice;
+pub use de::from_reader;
+pub use de::from_str_borrowed;
+pub use de::StreamDeserializer;
+pub use ser::to_string;
+pub use ser::to_string_pretty;
+pub use ser::to_writer;
+pub use ser::LineWriter;
+pub use convert::FromJson;
+pub use convert::ToJson;
+#[cfg(feature = "serde")]
+pub use self::serde::Deserializer;
+#[cfg(feature = "serde")]
+pub use self::serde::Serializer;
@@ -0,0 +1,504 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::de;
+use super::value::Value;
+use super::value::Map;
+use super::value::PointerError;
+use super::value::pointer;
+
+// JSON Patch (RFC 6902).
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Patch(pub Vec<Operation>);
+
+#[derive(Debug)]
+pub enum PatchError {
+    // The document could not be parsed as JSON.
+    Parse(de::Error),
+    // The document is JSON but not a well-formed patch.
+    Syntax(String),
+    // Operation `operation` referred to a location that cannot be used.
+    Pointer { operation: usize, error: PointerError },
+    // A "test" operation did not match.
+    TestFailed { operation: usize, path: String },
+    // A "move" whose target lies inside the value being moved.
+    MoveIntoChild { operation: usize, from: String, path: String },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PatchError::Parse(ref err) => write!(f, "{}", err),
+            PatchError::Syntax(ref message) => write!(f, "invalid patch: {}", message),
+            PatchError::Pointer { operation, ref error } => {
+                write!(f, "operation {}: {}", operation, error)
+            }
+            PatchError::TestFailed { operation, ref path } => {
+                write!(f, "operation {}: test failed at {:?}", operation, path)
+            }
+            PatchError::MoveIntoChild { operation, ref from, ref path } => {
+                write!(f, "operation {}: cannot move {:?} into its own child {:?}", operation, from, path)
+            }
+        }
+    }
+}
+
+impl error::Error for PatchError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            PatchError::Parse(ref err) => Some(err),
+            PatchError::Pointer { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Operation {
+    pub fn op(&self) -> &'static str {
+        match *self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Test { .. } => "test",
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match *self {
+            Operation::Add { ref path, .. }
+            | Operation::Remove { ref path }
+            | Operation::Replace { ref path, .. }
+            | Operation::Move { ref path, .. }
+            | Operation::Copy { ref path, .. }
+            | Operation::Test { ref path, .. } => path,
+        }
+    }
+
+    pub fn from_value(value: &Value) -> Result<Operation, PatchError> {
+        let map = match value.as_object() {
+            Some(map) => map,
+            None => return Err(PatchError::Syntax(String::from("operation must be an object"))),
+        };
+        let member = |name: &str| -> Result<String, PatchError> {
+            match map.get(name) {
+                Some(Value::String(str)) => Ok(str.clone()),
+                Some(_) => Err(PatchError::Syntax(format!("{:?} must be a string", name))),
+                None => Err(PatchError::Syntax(format!("missing {:?}", name))),
+            }
+        };
+        let value = || -> Result<Value, PatchError> {
+            match map.get("value") {
+                Some(value) => Ok(value.clone()),
+                None => Err(PatchError::Syntax(String::from("missing \"value\""))),
+            }
+        };
+
+        let path = member("path")?;
+        match member("op")?.as_str() {
+            "add" => Ok(Operation::Add { path, value: value()? }),
+            "remove" => Ok(Operation::Remove { path }),
+            "replace" => Ok(Operation::Replace { path, value: value()? }),
+            "move" => Ok(Operation::Move { from: member("from")?, path }),
+            "copy" => Ok(Operation::Copy { from: member("from")?, path }),
+            "test" => Ok(Operation::Test { path, value: value()? }),
+            op => Err(PatchError::Syntax(format!("unknown op {:?}", op))),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        map.insert(String::from("op"), Value::String(String::from(self.op())));
+        match *self {
+            Operation::Move { ref from, .. } | Operation::Copy { ref from, .. } => {
+                map.insert(String::from("from"), Value::String(from.clone()));
+            }
+            _ => {}
+        }
+        map.insert(String::from("path"), Value::String(String::from(self.path())));
+        match *self {
+            Operation::Add { ref value, .. }
+            | Operation::Replace { ref value, .. }
+            | Operation::Test { ref value, .. } => {
+                map.insert(String::from("value"), value.clone());
+            }
+            _ => {}
+        }
+        Value::Object(map)
+    }
+}
+
+impl Patch {
+    pub fn new() -> Patch {
+        Patch(Vec::new())
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn from_value(value: &Value) -> Result<Patch, PatchError> {
+        match value.as_array() {
+            Some(list) => {
+                let operations = list.iter().map(Operation::from_value).collect::<Result<_, _>>()?;
+                Ok(Patch(operations))
+            }
+            None => {
+                Err(PatchError::Syntax(String::from("patch must be an array")))
+            }
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        Value::Array(self.0.iter().map(Operation::to_value).collect())
+    }
+}
+
+impl FromStr for Patch {
+    type Err = PatchError;
+
+    // An operation repeating a member, such as two "op"s, is not a valid
+    // patch (RFC 6902, appendix A.13), so no occurrence is allowed to win.
+    // Values carried by operations are ordinary JSON and may repeat keys.
+    fn from_str(str: &str) -> Result<Patch, PatchError> {
+        let value = de::De::new(str).deserialization().map_err(PatchError::Parse)?;
+        check_members(str).map_err(PatchError::Parse)?;
+        Patch::from_value(&value)
+    }
+}
+
+// Rejects a repeated member of an operation, the objects directly inside
+// the patch array.
+fn check_members(str: &str) -> Result<(), de::Error> {
+    let mut parser = de::Parser::new(de::StrRead::new(str));
+    let mut array = false;
+    let mut seen = HashMap::new();
+    while let Some(event) = parser.next_event()? {
+        match event {
+            de::Event::StartArray if parser.depth() == 1 => {
+                array = true;
+            }
+            de::Event::StartObject if parser.depth() == 2 => {
+                seen.clear();
+            }
+            de::Event::Key(key) if array && parser.depth() == 2 => {
+                let position = parser.event_position();
+                if let Some(&previous) = seen.get(&*key) {
+                    return Err(de::options::duplicate_error(&key, position, previous))
+                }
+                seen.insert(key.into_owned(), position);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
+
+// Applies every operation in order. The patch is atomic: if any operation
+// fails, `doc` is left exactly as it was.
+pub fn apply(doc: &mut Value, patch: &Patch) -> Result<(), PatchError> {
+    let mut target = doc.clone();
+    for (index, operation) in patch.0.iter().enumerate() {
+        apply_operation(&mut target, index, operation)?;
+    }
+    *doc = target;
+
+    Ok(())
+}
+
+fn apply_operation(doc: &mut Value, index: usize, operation: &Operation) -> Result<(), PatchError> {
+    let pointer_error = |error| PatchError::Pointer { operation: index, error };
+    let parse = |path: &str| pointer::parse_pointer(path).map_err(pointer_error);
+
+    match *operation {
+        Operation::Add { ref path, ref value } => {
+            pointer::insert(doc, &parse(path)?, value.clone()).map_err(pointer_error)?;
+        }
+        Operation::Remove { ref path } => {
+            pointer::remove(doc, &parse(path)?).map_err(pointer_error)?;
+        }
+        Operation::Replace { ref path, ref value } => {
+            let target = pointer::resolve_mut(doc, &parse(path)?).map_err(pointer_error)?;
+            *target = value.clone();
+        }
+        Operation::Move { ref from, ref path } => {
+            let from_tokens = parse(from)?;
+            let path_tokens = parse(path)?;
+            if from_tokens.len() < path_tokens.len() && path_tokens.starts_with(&from_tokens) {
+                return Err(PatchError::MoveIntoChild {
+                    operation: index,
+                    from: from.clone(),
+                    path: path.clone(),
+                })
+            }
+            let value = pointer::remove(doc, &from_tokens).map_err(pointer_error)?;
+            pointer::insert(doc, &path_tokens, value).map_err(pointer_error)?;
+        }
+        Operation::Copy { ref from, ref path } => {
+            let value = pointer::resolve(doc, &parse(from)?).map_err(pointer_error)?.clone();
+            pointer::insert(doc, &parse(path)?, value).map_err(pointer_error)?;
+        }
+        Operation::Test { ref path, ref value } => {
+            // `Value`'s equality is the one RFC 6902 asks for: member order is
+            // irrelevant and numbers compare by numeric value.
+            let target = pointer::resolve(doc, &parse(path)?).map_err(pointer_error)?;
+            if target != value {
+                return Err(PatchError::TestFailed {
+                    operation: index,
+                    path: path.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Produces a patch turning `a` into `b`. Objects are compared member by
+// member and arrays by edit distance, so unchanged elements are kept and only
+// the differing parts are added, removed or replaced.
+pub fn diff(a: &Value, b: &Value) -> Patch {
+    let mut patch = Patch::new();
+    let mut path = Vec::new();
+    diff_value(a, b, &mut path, &mut patch.0);
+    patch
+}
+
+fn diff_value(a: &Value, b: &Value, path: &mut Vec<String>, operations: &mut Vec<Operation>) {
+    if a == b {
+        return
+    }
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            diff_object(a, b, path, operations);
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            diff_array(a, b, path, operations);
+        }
+        _ => {
+            operations.push(Operation::Replace {
+                path: pointer::to_pointer(path),
+                value: b.clone(),
+            });
+        }
+    }
+}
+
+fn diff_object(a: &Map, b: &Map, path: &mut Vec<String>, operations: &mut Vec<Operation>) {
+    for (key, a) in a {
+        path.push(key.clone());
+        match b.get(key) {
+            Some(b) => diff_value(a, b, path, operations),
+            None => operations.push(Operation::Remove { path: pointer::to_pointer(path) }),
+        }
+        path.pop();
+    }
+    for (key, b) in b {
+        if !a.contains_key(key) {
+            path.push(key.clone());
+            operations.push(Operation::Add {
+                path: pointer::to_pointer(path),
+                value: b.clone(),
+            });
+            path.pop();
+        }
+    }
+}
+
+enum Edit {
+    Keep,
+    Change,
+    Remove,
+    Add,
+}
+
+// The cost table below has a cell for every pair of elements left once the
+// common prefix and suffix are set aside. Past this many cells, elements are
+// paired by index instead, which needs no table but may not be minimal.
+const MAX_TABLE_CELLS: usize = 1 << 20;
+
+fn diff_array(a: &[Value], b: &[Value], path: &mut Vec<String>, operations: &mut Vec<Operation>) {
+    let prefix = a.iter().zip(b).take_while(|&(a, b)| a == b).count();
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let suffix = a.iter().rev().zip(b.iter().rev()).take_while(|&(a, b)| a == b).count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+    if (a.len() + 1).saturating_mul(b.len() + 1) > MAX_TABLE_CELLS {
+        diff_by_index(a, b, prefix, path, operations);
+    } else {
+        diff_by_edits(a, b, prefix, path, operations);
+    }
+}
+
+fn diff_by_index(a: &[Value], b: &[Value], start: usize, path: &mut Vec<String>, operations: &mut Vec<Operation>) {
+    let common = a.len().min(b.len());
+    for (index, (a, b)) in a.iter().zip(b).enumerate() {
+        path.push((start + index).to_string());
+        diff_value(a, b, path, operations);
+        path.pop();
+    }
+    // Surplus elements of `a` all sit at the same index once those before
+    // them are removed.
+    path.push((start + common).to_string());
+    for _ in common..a.len() {
+        operations.push(Operation::Remove { path: pointer::to_pointer(path) });
+    }
+    path.pop();
+    for (index, b) in b.iter().enumerate().skip(common) {
+        path.push((start + index).to_string());
+        operations.push(Operation::Add {
+            path: pointer::to_pointer(path),
+            value: b.clone(),
+        });
+        path.pop();
+    }
+}
+
+fn diff_by_edits(a: &[Value], b: &[Value], start: usize, path: &mut Vec<String>, operations: &mut Vec<Operation>) {
+    // costs[i][j] is the number of operations turning a[i..] into b[j..].
+    let (n, m) = (a.len(), b.len());
+    let mut costs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..=n).rev() {
+        for j in (0..=m).rev() {
+            costs[i][j] = if i == n {
+                m - j
+            } else if j == m {
+                n - i
+            } else if a[i] == b[j] {
+                costs[i + 1][j + 1]
+            } else {
+                1 + costs[i + 1][j + 1].min(costs[i + 1][j]).min(costs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j, mut index) = (0, 0, start);
+    while i < n || j < m {
+        let edit = if i == n {
+            Edit::Add
+        } else if j == m {
+            Edit::Remove
+        } else if a[i] == b[j] {
+            Edit::Keep
+        } else if costs[i][j] == 1 + costs[i + 1][j + 1] {
+            Edit::Change
+        } else if costs[i][j] == 1 + costs[i + 1][j] {
+            Edit::Remove
+        } else {
+            Edit::Add
+        };
+
+        path.push(index.to_string());
+        match edit {
+            Edit::Keep => {
+                i += 1;
+                j += 1;
+                index += 1;
+            }
+            Edit::Change => {
+                diff_value(&a[i], &b[j], path, operations);
+                i += 1;
+                j += 1;
+                index += 1;
+            }
+            Edit::Remove => {
+                operations.push(Operation::Remove { path: pointer::to_pointer(path) });
+                i += 1;
+            }
+            Edit::Add => {
+                operations.push(Operation::Add {
+                    path: pointer::to_pointer(path),
+                    value: b[j].clone(),
+                });
+                j += 1;
+                index += 1;
+            }
+        }
+        path.pop();
+    }
+}
+
+// JSON Merge Patch (RFC 7396): a patch is a partial document where `null`
+// deletes a member, objects merge recursively and anything else replaces.
+
+impl Value {
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let members = match *patch {
+            Value::Object(ref members) => members,
+            _ => {
+                *self = patch.clone();
+                return
+            }
+        };
+        if !self.is_object() {
+            *self = Value::Object(Map::new());
+        }
+        if let Value::Object(ref mut map) = *self {
+            for (key, value) in members {
+                if value.is_null() {
+                    map.remove(key);
+                } else {
+                    map.entry(key.as_str()).or_insert(Value::Null).merge_patch(value);
+                }
+            }
+        }
+    }
+}
+
+// Produces a merge patch turning `a` into `b`. The format cannot express
+// everything, so applying the result is lossy in two cases:
+//
+// * a `null` member of an object in `b` comes out as a removed member,
+//   because `null` in a merge patch means "delete";
+// * arrays are never merged, so any change inside one replaces it whole.
+pub fn merge_patch_diff(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut patch = Map::new();
+            for key in a.keys() {
+                if !b.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            for (key, b) in b {
+                match a.get(key) {
+                    Some(a) if a == b => {}
+                    Some(a) => {
+                        patch.insert(key.clone(), merge_patch_diff(a, b));
+                    }
+                    None => {
+                        patch.insert(key.clone(), b.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        _ => {
+            b.clone()
+        }
+    }
+}
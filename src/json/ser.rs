@@ -0,0 +1,314 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::value::Value;
+use super::value::Number;
+use super::value::Num;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    NonFinite(f64),
+    // Raised by a type being serialized, or for data JSON cannot express.
+    Custom(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "io error: {}", err),
+            Error::NonFinite(n) => write!(f, "cannot serialize non-finite number {}", n),
+            Error::Custom(ref message) => f.write_str(message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::NonFinite(_) | Error::Custom(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+// What to emit for `NaN` and the infinities, which JSON cannot represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinite {
+    Null,
+    Error,
+}
+
+pub struct Ser<W> {
+    writer: W,
+    indent: Option<String>,
+    non_finite: NonFinite,
+    depth: usize,
+}
+
+impl<W: io::Write> Ser<W> {
+    pub fn new(writer: W) -> Ser<W> {
+        Ser {
+            writer,
+            indent: None,
+            non_finite: NonFinite::Null,
+            depth: 0,
+        }
+    }
+
+    pub fn pretty(writer: W) -> Ser<W> {
+        Ser::new(writer).indent("  ")
+    }
+
+    pub fn indent(mut self, indent: &str) -> Ser<W> {
+        self.indent = Some(String::from(indent));
+        self
+    }
+
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Ser<W> {
+        self.non_finite = non_finite;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn serialization(&mut self, value: &Value) -> Result<(), Error> {
+        self.write_value(value)
+    }
+
+    pub fn write_value(&mut self, value: &Value) -> Result<(), Error> {
+        match *value {
+            Value::Null => {
+                self.writer.write_all(b"null")?;
+            }
+            Value::Boolean(true) => {
+                self.writer.write_all(b"true")?;
+            }
+            Value::Boolean(false) => {
+                self.writer.write_all(b"false")?;
+            }
+            Value::Number(ref number) => {
+                self.write_number(number)?;
+            }
+            Value::String(ref str) => {
+                self.write_string(str)?;
+            }
+            Value::Array(ref list) => {
+                self.begin_container(b"[")?;
+                for (index, value) in list.iter().enumerate() {
+                    self.begin_item(index == 0)?;
+                    self.write_value(value)?;
+                }
+                self.end_container(b"]", list.is_empty())?;
+            }
+            Value::Object(ref map) => {
+                self.begin_container(b"{")?;
+                for (index, (key, value)) in map.iter().enumerate() {
+                    self.begin_item(index == 0)?;
+                    self.write_string(key)?;
+                    self.write_colon()?;
+                    self.write_value(value)?;
+                }
+                self.end_container(b"}", map.is_empty())?;
+            }
+            Value::Raw(ref raw) => {
+                self.write_raw(raw.get().as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_number(&mut self, number: &Number) -> Result<(), Error> {
+        match number.num {
+            Num::Float(n) if !n.is_finite() => {
+                match self.non_finite {
+                    NonFinite::Null => self.writer.write_all(b"null")?,
+                    NonFinite::Error => return Err(Error::NonFinite(n)),
+                }
+            }
+            // The text, exactly as it was parsed.
+            #[cfg(feature = "arbitrary_precision")]
+            _ => {
+                self.writer.write_all(number.as_str().as_bytes())?;
+            }
+            #[cfg(not(feature = "arbitrary_precision"))]
+            Num::PosInt(n) => {
+                write!(self.writer, "{}", n)?;
+            }
+            #[cfg(not(feature = "arbitrary_precision"))]
+            Num::NegInt(n) => {
+                write!(self.writer, "{}", n)?;
+            }
+            #[cfg(not(feature = "arbitrary_precision"))]
+            Num::Float(n) => {
+                // `Debug` prints the shortest representation that round-trips,
+                // switching to exponent notation for very large or small values.
+                write!(self.writer, "{:?}", n)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_string(&mut self, str: &str) -> Result<(), Error> {
+        self.writer.write_all(b"\"")?;
+        let bytes = str.as_bytes();
+        let mut start = 0;
+        for (index, &byte) in bytes.iter().enumerate() {
+            let escape: &[u8] = match byte {
+                b'"' => b"\\\"",
+                b'\\' => b"\\\\",
+                b'\x08' => b"\\b",
+                b'\x0c' => b"\\f",
+                b'\n' => b"\\n",
+                b'\r' => b"\\r",
+                b'\t' => b"\\t",
+                0x00..=0x1f => b"",
+                _ => continue,
+            };
+            self.writer.write_all(&bytes[start..index])?;
+            if escape.is_empty() {
+                write!(self.writer, "\\u{:04x}", byte)?;
+            } else {
+                self.writer.write_all(escape)?;
+            }
+            start = index + 1;
+        }
+        self.writer.write_all(&bytes[start..])?;
+        self.writer.write_all(b"\"")?;
+
+        Ok(())
+    }
+
+    // The pieces of an array or object, for callers that produce one without
+    // having a `Value` for it.
+    pub(crate) fn begin_container(&mut self, open: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(open)?;
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    pub(crate) fn begin_item(&mut self, first: bool) -> Result<(), Error> {
+        if !first {
+            self.writer.write_all(b",")?;
+        }
+        self.write_newline()
+    }
+
+    pub(crate) fn write_colon(&mut self) -> Result<(), Error> {
+        match self.indent {
+            Some(_) => self.writer.write_all(b": ")?,
+            None => self.writer.write_all(b":")?,
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn end_container(&mut self, close: &[u8], empty: bool) -> Result<(), Error> {
+        self.depth -= 1;
+        if !empty {
+            self.write_newline()?;
+        }
+        self.writer.write_all(close)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn write_raw(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(bytes)?;
+
+        Ok(())
+    }
+
+    fn write_newline(&mut self) -> Result<(), Error> {
+        if let Some(ref indent) = self.indent {
+            self.writer.write_all(b"\n")?;
+            for _ in 0..self.depth {
+                self.writer.write_all(indent.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Writes newline-delimited JSON (JSON Lines): each value compact, on a line
+// of its own.
+pub struct LineWriter<W> {
+    ser: Ser<W>,
+}
+
+impl<W: io::Write> LineWriter<W> {
+    pub fn new(writer: W) -> LineWriter<W> {
+        LineWriter {
+            ser: Ser::new(writer),
+        }
+    }
+
+    pub fn non_finite(mut self, non_finite: NonFinite) -> LineWriter<W> {
+        self.ser = self.ser.non_finite(non_finite);
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.ser.into_inner()
+    }
+
+    pub fn write(&mut self, value: &Value) -> Result<(), Error> {
+        self.ser.serialization(value)?;
+        self.ser.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.ser.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+pub fn to_writer<W: io::Write>(writer: W, value: &Value) -> Result<(), Error> {
+    Ser::new(writer).serialization(value)
+}
+
+pub fn to_writer_pretty<W: io::Write>(writer: W, value: &Value) -> Result<(), Error> {
+    Ser::pretty(writer).serialization(value)
+}
+
+pub fn to_writer_lines<'v, W, I>(writer: W, values: I) -> Result<(), Error>
+where
+    W: io::Write,
+    I: IntoIterator<Item = &'v Value>,
+{
+    let mut writer = LineWriter::new(writer);
+    for value in values {
+        writer.write(value)?;
+    }
+    writer.flush()
+}
+
+pub fn to_string(value: &Value) -> Result<String, Error> {
+    to_string_with(Ser::new(Vec::new()), value)
+}
+
+pub fn to_string_pretty(value: &Value) -> Result<String, Error> {
+    to_string_with(Ser::pretty(Vec::new()), value)
+}
+
+pub fn to_string_with(mut ser: Ser<Vec<u8>>, value: &Value) -> Result<String, Error> {
+    ser.serialization(value)?;
+    let buf = ser.into_inner();
+    Ok(String::from_utf8(buf).expect("serializer only writes UTF-8"))
+}
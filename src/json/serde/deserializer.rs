@@ -0,0 +1,423 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+
+use serde::de;
+use serde::de::IntoDeserializer;
+
+use super::super::de::Error;
+use super::super::de::ErrorKind;
+use super::super::de::Event;
+use super::super::de::IoRead;
+use super::super::de::Parser;
+use super::super::de::ParserOptions;
+use super::super::de::Read;
+use super::super::de::StrRead;
+use super::super::value::Num;
+#[cfg(feature = "arbitrary_precision")]
+use super::super::value::number::Integer;
+#[cfg(feature = "arbitrary_precision")]
+use super::NUMBER_TOKEN;
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Error {
+        Error::custom(message)
+    }
+}
+
+// A `serde::Deserializer` reading the events of a `Parser`, so typed data is
+// checked against exactly the grammar `De` uses. Errors raised by the types
+// being deserialized are given the position of the token they concern.
+// Nested values are visited recursively whatever `explicit_stack` says, so
+// `max_depth` is what protects the call stack here.
+pub struct Deserializer<'a, R> {
+    parser: Parser<'a, R>,
+    peeked: Option<Event<'a>>,
+}
+
+impl<'a> Deserializer<'a, StrRead<'a>> {
+    pub fn new(str: &'a str) -> Deserializer<'a, StrRead<'a>> {
+        Deserializer::from_parser(Parser::new(StrRead::new(str)))
+    }
+}
+
+impl<'a, R: io::Read> Deserializer<'a, IoRead<R>> {
+    pub fn from_reader(reader: R) -> Deserializer<'a, IoRead<R>> {
+        Deserializer::from_parser(Parser::from_reader(reader))
+    }
+
+    pub fn from_reader_with(reader: R, options: ParserOptions) -> Deserializer<'a, IoRead<R>> {
+        Deserializer::from_parser(Parser::from_reader_with(reader, options))
+    }
+}
+
+impl<'a, R: Read<'a>> Deserializer<'a, R> {
+    pub fn from_parser(parser: Parser<'a, R>) -> Deserializer<'a, R> {
+        Deserializer {
+            parser,
+            peeked: None,
+        }
+    }
+
+    pub fn parser(&self) -> &Parser<'a, R> {
+        &self.parser
+    }
+
+    // Checks that nothing but whitespace follows the value just read.
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.next_event()? {
+            Some(_) => Err(Error::new(ErrorKind::TrailingCharacters, None, None, self.parser.event_position())),
+            None => Ok(()),
+        }
+    }
+
+    fn next_event(&mut self) -> Result<Option<Event<'a>>, Error> {
+        match self.peeked.take() {
+            Some(event) => Ok(Some(event)),
+            None => self.parser.next_event(),
+        }
+    }
+
+    fn peek_event(&mut self) -> Result<Option<&Event<'a>>, Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.parser.next_event()?;
+        }
+        Ok(self.peeked.as_ref())
+    }
+
+    fn match_value(&mut self) -> Result<Event<'a>, Error> {
+        match self.next_event()? {
+            Some(Event::Key(_)) | Some(Event::EndObject) | Some(Event::EndArray) => {
+                Err(self.unexpected("value"))
+            }
+            Some(event) => {
+                Ok(event)
+            }
+            None => {
+                Err(Error::new(ErrorKind::UnexpectedEof, Some(String::from("value")), None, self.parser.position()))
+            }
+        }
+    }
+
+    // Consumes the end of a container whose visitor stopped reading before
+    // it was exhausted.
+    fn match_close(&mut self, close: &str) -> Result<(), Error> {
+        match self.next_event()? {
+            Some(Event::EndObject) if close == "'}'" => Ok(()),
+            Some(Event::EndArray) if close == "']'" => Ok(()),
+            _ => Err(self.unexpected(close)),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        Error::new(ErrorKind::UnexpectedCharacter, Some(String::from(expected)), None, self.parser.event_position())
+    }
+
+    fn locate(&self, err: Error) -> Error {
+        err.locate(self.parser.event_position())
+    }
+}
+
+impl<'de, R: Read<'de>> de::Deserializer<'de> for &mut Deserializer<'de, R> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = match self.match_value()? {
+            Event::StartObject => {
+                let mut map = MapAccess::new(self);
+                let value = visitor.visit_map(&mut map);
+                let done = map.done;
+                let value = value.map_err(|err| self.locate(err))?;
+                if !done {
+                    self.match_close("'}'")?;
+                }
+                return Ok(value)
+            }
+            Event::StartArray => {
+                let mut seq = SeqAccess::new(self);
+                let value = visitor.visit_seq(&mut seq);
+                let done = seq.done;
+                let value = value.map_err(|err| self.locate(err))?;
+                if !done {
+                    self.match_close("']'")?;
+                }
+                return Ok(value)
+            }
+            Event::String(Cow::Borrowed(str)) => visitor.visit_borrowed_str(str),
+            Event::String(Cow::Owned(str)) => visitor.visit_string(str),
+            Event::Number(number) => {
+                match number.num {
+                    Num::PosInt(n) => visitor.visit_u64(n),
+                    Num::NegInt(n) => visitor.visit_i64(n),
+                    // See `Serialize for Number` for integers beyond 64 bits.
+                    #[cfg(feature = "arbitrary_precision")]
+                    Num::Float(n) if number.is_integer() => {
+                        match number.integer("i128") {
+                            Ok(Integer::Unsigned(n)) => visitor.visit_u128(n),
+                            Ok(Integer::Signed(n)) => visitor.visit_i128(n),
+                            Err(_) => visitor.visit_f64(n),
+                        }
+                    }
+                    Num::Float(n) => visitor.visit_f64(n),
+                }
+            }
+            Event::Bool(b) => visitor.visit_bool(b),
+            Event::Null => visitor.visit_unit(),
+            Event::Key(_) | Event::EndObject | Event::EndArray => unreachable!(),
+        };
+
+        value.map_err(|err| self.locate(err))
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(Event::Null) = self.peek_event()? {
+            self.next_event()?;
+            return visitor.visit_none().map_err(|err| self.locate(err))
+        }
+        visitor.visit_some(self)
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // `NUMBER_TOKEN` asks for the value with numbers as their exact text.
+    #[cfg(feature = "arbitrary_precision")]
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        if name != NUMBER_TOKEN {
+            return visitor.visit_newtype_struct(self)
+        }
+        match self.peek_event()? {
+            Some(Event::Number(_)) => {}
+            _ => return self.deserialize_any(visitor),
+        }
+        let text = match self.next_event()? {
+            Some(Event::Number(number)) => String::from(number.as_str()),
+            _ => unreachable!(),
+        };
+        let members = de::value::MapDeserializer::new(std::iter::once((NUMBER_TOKEN, text)));
+        visitor.visit_map(members).map_err(|err| self.locate(err))
+    }
+
+    // Enums are written the way `Serializer` writes them: a unit variant as
+    // a string, any other variant as an object with the variant name as its
+    // only key.
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.match_value()? {
+            Event::String(Cow::Borrowed(str)) => {
+                let value = visitor.visit_enum(de::value::BorrowedStrDeserializer::new(str));
+                value.map_err(|err| self.locate(err))
+            }
+            Event::String(Cow::Owned(str)) => {
+                let value = visitor.visit_enum(str.into_deserializer());
+                value.map_err(|err: Error| self.locate(err))
+            }
+            Event::StartObject => {
+                let value = visitor.visit_enum(VariantAccess { de: self });
+                let value = value.map_err(|err| self.locate(err))?;
+                self.match_close("'}'")?;
+                Ok(value)
+            }
+            _ => {
+                Err(self.unexpected("string or object"))
+            }
+        }
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier
+    }
+}
+
+struct SeqAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+    done: bool,
+}
+
+impl<'a, 'de, R> SeqAccess<'a, 'de, R> {
+    fn new(de: &'a mut Deserializer<'de, R>) -> SeqAccess<'a, 'de, R> {
+        SeqAccess {
+            de,
+            done: false,
+        }
+    }
+}
+
+impl<'de, R: Read<'de>> de::SeqAccess<'de> for SeqAccess<'_, 'de, R> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if self.done {
+            return Ok(None)
+        }
+        if let Some(Event::EndArray) = self.de.peek_event()? {
+            self.de.next_event()?;
+            self.done = true;
+            return Ok(None)
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+struct MapAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+    done: bool,
+}
+
+impl<'a, 'de, R> MapAccess<'a, 'de, R> {
+    fn new(de: &'a mut Deserializer<'de, R>) -> MapAccess<'a, 'de, R> {
+        MapAccess {
+            de,
+            done: false,
+        }
+    }
+}
+
+impl<'de, R: Read<'de>> de::MapAccess<'de> for MapAccess<'_, 'de, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        if self.done {
+            return Ok(None)
+        }
+        match self.de.next_event()? {
+            Some(Event::Key(key)) => {
+                let key = seed.deserialize(KeyDeserializer { key });
+                key.map(Some).map_err(|err| self.de.locate(err))
+            }
+            Some(Event::EndObject) => {
+                self.done = true;
+                Ok(None)
+            }
+            _ => {
+                Err(self.de.unexpected("key"))
+            }
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct VariantAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<'de, R>,
+}
+
+impl<'a, 'de, R: Read<'de>> de::EnumAccess<'de> for VariantAccess<'a, 'de, R> {
+    type Error = Error;
+    type Variant = VariantAccess<'a, 'de, R>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Error> {
+        match self.de.next_event()? {
+            Some(Event::Key(key)) => {
+                let variant = seed.deserialize(KeyDeserializer { key });
+                let variant = variant.map_err(|err| self.de.locate(err))?;
+                Ok((variant, self))
+            }
+            _ => {
+                Err(self.de.unexpected("variant name"))
+            }
+        }
+    }
+}
+
+impl<'de, R: Read<'de>> de::VariantAccess<'de> for VariantAccess<'_, 'de, R> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self.de, visitor)
+    }
+}
+
+// Object keys are always strings; maps keyed by numbers or booleans read
+// them from the key's text.
+struct KeyDeserializer<'de> {
+    key: Cow<'de, str>,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.key.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(&self.key), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.key {
+            Cow::Borrowed(key) => visitor.visit_borrowed_str(key),
+            Cow::Owned(key) => visitor.visit_string(key),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.key.into_owned().into_deserializer())
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
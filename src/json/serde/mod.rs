@@ -0,0 +1,68 @@
+use std::io;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::de;
+use super::ser;
+
+pub mod deserializer;
+pub mod serializer;
+mod value;
+
+pub use deserializer::Deserializer;
+pub use serializer::Serializer;
+pub use serializer::Compound;
+
+// Under `arbitrary_precision`, the name under which a number's text passes
+// through serde, as a struct with this as its only field. Other formats see
+// such a struct, as they do with serde_json's equivalent; `Serializer` writes
+// the number and `Deserializer` produces it when a `Value` asks for it.
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) const NUMBER_TOKEN: &str = "$json_parser::private::Number";
+
+// Generic counterparts of `json::from_str`, `json::to_string` and friends,
+// for any type implementing serde's traits.
+
+pub fn from_str<'a, T: Deserialize<'a>>(str: &'a str) -> Result<T, de::Error> {
+    let mut deserializer = Deserializer::new(str);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok(value)
+}
+
+pub fn from_slice<'a, T: Deserialize<'a>>(slice: &'a [u8]) -> Result<T, de::Error> {
+    from_str(de::from_utf8(slice)?)
+}
+
+pub fn from_reader<R: io::Read, T: DeserializeOwned>(reader: R) -> Result<T, de::Error> {
+    let mut deserializer = Deserializer::from_reader(reader);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok(value)
+}
+
+pub fn to_writer<W: io::Write, T: ?Sized + Serialize>(writer: W, value: &T) -> Result<(), ser::Error> {
+    value.serialize(&mut Serializer::new(writer))
+}
+
+pub fn to_writer_pretty<W: io::Write, T: ?Sized + Serialize>(writer: W, value: &T) -> Result<(), ser::Error> {
+    value.serialize(&mut Serializer::pretty(writer))
+}
+
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, ser::Error> {
+    to_string_with(Serializer::new(Vec::new()), value)
+}
+
+pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T) -> Result<String, ser::Error> {
+    to_string_with(Serializer::pretty(Vec::new()), value)
+}
+
+fn to_string_with<T: ?Sized + Serialize>(mut serializer: Serializer<Vec<u8>>, value: &T) -> Result<String, ser::Error> {
+    value.serialize(&mut serializer)?;
+    let buf = serializer.into_inner();
+    Ok(String::from_utf8(buf).expect("serializer only writes UTF-8"))
+}
@@ -0,0 +1,535 @@
+use std::fmt;
+use std::io;
+
+use serde::ser;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+use super::super::ser::Error;
+use super::super::ser::NonFinite;
+use super::super::ser::Ser;
+use super::super::value::Num;
+use super::super::value::Number;
+#[cfg(feature = "arbitrary_precision")]
+use super::NUMBER_TOKEN;
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Error {
+        Error::Custom(message.to_string())
+    }
+}
+
+// A `serde::Serializer` writing JSON through a `Ser`, so typed data comes out
+// formatted exactly like a `Value` would. Enums use the externally tagged
+// form: unit variants as strings, other variants as single-member objects.
+pub struct Serializer<W> {
+    ser: Ser<W>,
+}
+
+impl<W: io::Write> Serializer<W> {
+    pub fn new(writer: W) -> Serializer<W> {
+        Serializer::from_ser(Ser::new(writer))
+    }
+
+    pub fn pretty(writer: W) -> Serializer<W> {
+        Serializer::from_ser(Ser::pretty(writer))
+    }
+
+    pub fn from_ser(ser: Ser<W>) -> Serializer<W> {
+        Serializer {
+            ser
+        }
+    }
+
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Serializer<W> {
+        self.ser = self.ser.non_finite(non_finite);
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.ser.into_inner()
+    }
+
+    fn write_num(&mut self, num: Num) -> Result<(), Error> {
+        self.ser.write_number(&Number::from(num))
+    }
+
+    fn write_display<T: fmt::Display>(&mut self, value: T) -> Result<(), Error> {
+        self.ser.write_raw(value.to_string().as_bytes())
+    }
+
+    // Opens the object wrapping a variant's content.
+    fn begin_variant(&mut self, variant: &str) -> Result<(), Error> {
+        self.ser.begin_container(b"{")?;
+        self.ser.begin_item(true)?;
+        self.ser.write_string(variant)?;
+        self.ser.write_colon()
+    }
+}
+
+impl<'s, W: io::Write> ser::Serializer for &'s mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'s, W>;
+    type SerializeTuple = Compound<'s, W>;
+    type SerializeTupleStruct = Compound<'s, W>;
+    type SerializeTupleVariant = Compound<'s, W>;
+    type SerializeMap = Compound<'s, W>;
+    type SerializeStruct = Compound<'s, W>;
+    type SerializeStructVariant = Compound<'s, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.ser.write_raw(if v { b"true" } else { b"false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        if v < 0 {
+            self.write_num(Num::NegInt(v))
+        } else {
+            self.write_num(Num::PosInt(v as u64))
+        }
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        self.write_display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.write_num(Num::PosInt(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        self.write_display(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.write_num(Num::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.ser.write_string(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.ser.write_string(v)
+    }
+
+    // Bytes become an array of numbers.
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        ser::Serializer::collect_seq(self, v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.ser.write_raw(b"null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.begin_variant(variant)?;
+        value.serialize(&mut *self)?;
+        self.ser.end_container(b"}", false)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'s, W>, Error> {
+        self.ser.begin_container(b"[")?;
+        Ok(Compound::new(self, b"]", false))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'s, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Compound<'s, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'s, W>, Error> {
+        self.begin_variant(variant)?;
+        self.ser.begin_container(b"[")?;
+        Ok(Compound::new(self, b"]", true))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'s, W>, Error> {
+        self.ser.begin_container(b"{")?;
+        Ok(Compound::new(self, b"}", false))
+    }
+
+    #[cfg_attr(not(feature = "arbitrary_precision"), allow(unused_variables))]
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Compound<'s, W>, Error> {
+        #[cfg(feature = "arbitrary_precision")]
+        if name == NUMBER_TOKEN {
+            let mut compound = Compound::new(self, b"", false);
+            compound.number = true;
+            return Ok(compound)
+        }
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'s, W>, Error> {
+        self.begin_variant(variant)?;
+        self.ser.begin_container(b"{")?;
+        Ok(Compound::new(self, b"}", true))
+    }
+}
+
+// An array or object being written; `variant` when it sits inside the
+// object naming an enum variant, which has to be closed as well. `number`
+// is the struct carrying an `arbitrary_precision` number, which is written
+// as the number alone.
+pub struct Compound<'s, W> {
+    ser: &'s mut Serializer<W>,
+    close: &'static [u8],
+    variant: bool,
+    number: bool,
+    empty: bool,
+}
+
+impl<'s, W: io::Write> Compound<'s, W> {
+    fn new(ser: &'s mut Serializer<W>, close: &'static [u8], variant: bool) -> Compound<'s, W> {
+        Compound {
+            ser,
+            close,
+            variant,
+            number: false,
+            empty: true,
+        }
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.ser.ser.begin_item(self.empty)?;
+        self.empty = false;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        if self.number {
+            // The text arrives as a string, which is written quoted; it is
+            // checked by parsing it back before being written as a number.
+            let mut text = Ser::new(Vec::new());
+            value.serialize(KeySerializer { ser: &mut text })?;
+            let text = String::from_utf8(text.into_inner()).expect("serializer only writes UTF-8");
+            let number: Number = text
+                .trim_matches('"')
+                .parse()
+                .map_err(|_| Error::Custom(format!("invalid number text {}", text)))?;
+            return self.ser.ser.write_number(&number)
+        }
+        self.ser.ser.begin_item(self.empty)?;
+        self.empty = false;
+        self.ser.ser.write_string(key)?;
+        self.ser.ser.write_colon()?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn close(self) -> Result<(), Error> {
+        if self.number {
+            return Ok(())
+        }
+        self.ser.ser.end_container(self.close, self.empty)?;
+        if self.variant {
+            self.ser.ser.end_container(b"}", false)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: io::Write> ser::SerializeSeq for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeTuple for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeTupleStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeTupleVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeMap for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.ser.ser.begin_item(self.empty)?;
+        self.empty = false;
+        key.serialize(KeySerializer { ser: &mut self.ser.ser })?;
+        self.ser.ser.write_colon()
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+impl<W: io::Write> ser::SerializeStructVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.close()
+    }
+}
+
+// Writes map keys. JSON keys are strings, so strings, characters, unit
+// variants, numbers and booleans are accepted, the last two as their text.
+struct KeySerializer<'s, W> {
+    ser: &'s mut Ser<W>,
+}
+
+fn key_must_be_a_string() -> Error {
+    Error::Custom(String::from("map key must be a string"))
+}
+
+macro_rules! serialize_key_as_text {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.ser.write_string(&v.to_string())
+            }
+        )*
+    };
+}
+
+impl<W: io::Write> ser::Serializer for KeySerializer<'_, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    serialize_key_as_text! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_char: char,
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.ser.write_string(v)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+        self.ser.write_string(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<(), Error>, Error> {
+        Err(key_must_be_a_string())
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use serde::de;
+use serde::ser;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::super::value::Map;
+use super::super::value::Num;
+use super::super::value::Number;
+use super::super::value::RawValue;
+use super::super::value::Value;
+#[cfg(feature = "arbitrary_precision")]
+use super::NUMBER_TOKEN;
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Boolean(b) => serializer.serialize_bool(b),
+            Value::Number(ref number) => number.serialize(serializer),
+            Value::String(ref str) => serializer.serialize_str(str),
+            Value::Array(ref list) => serializer.collect_seq(list),
+            Value::Object(ref map) => map.serialize(serializer),
+            Value::Raw(ref raw) => raw.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Number {
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.num {
+            Num::PosInt(n) => serializer.serialize_u64(n),
+            Num::NegInt(n) => serializer.serialize_i64(n),
+            Num::Float(n) => serializer.serialize_f64(n),
+        }
+    }
+
+    // The text goes out under `NUMBER_TOKEN`. NaN and the infinities have
+    // none, and go out as floats for the serializer's policy on them.
+    #[cfg(feature = "arbitrary_precision")]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match self.num {
+            Num::Float(n) if !n.is_finite() => {
+                serializer.serialize_f64(n)
+            }
+            _ => {
+                let mut number = serializer.serialize_struct(NUMBER_TOKEN, 1)?;
+                number.serialize_field(NUMBER_TOKEN, self.as_str())?;
+                number.end()
+            }
+        }
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self)
+    }
+}
+
+// Serde has no notion of verbatim text, so a raw value is serialized as the
+// value it holds; only `json::to_string` and friends copy it unchanged.
+impl Serialize for RawValue {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.to_value() {
+            Ok(value) => value.serialize(serializer),
+            Err(err) => Err(ser::Error::custom(err)),
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> de::Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(Number::from(v)))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+        Ok(Value::Number(Number::from(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(Number::from(v)))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+        Ok(Value::Number(Number::from(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Number(Number::from(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(String::from(v)))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut list = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            list.push(value);
+        }
+        Ok(Value::Array(list))
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Value, A::Error> {
+        MapVisitor.visit_map(map).map(Value::Object)
+    }
+
+    // A number's text comes as an object whose only key is `NUMBER_TOKEN`.
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = Map::new();
+        match access.next_key::<String>()? {
+            Some(key) if key == NUMBER_TOKEN => {
+                let text: String = access.next_value()?;
+                return text.parse().map(Value::Number).map_err(de::Error::custom)
+            }
+            Some(key) => {
+                map.insert(key, access.next_value()?);
+            }
+            None => {
+                return Ok(Value::Object(map))
+            }
+        }
+        insert_members(&mut map, access)?;
+
+        Ok(Value::Object(map))
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+
+    // Asks for numbers as text, which `Deserializer` understands; to other
+    // formats this is a newtype struct like any other.
+    #[cfg(feature = "arbitrary_precision")]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_newtype_struct(NUMBER_TOKEN, ValueVisitor)
+    }
+}
+
+struct MapVisitor;
+
+impl<'de> de::Visitor<'de> for MapVisitor {
+    type Value = Map;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    // A repeated key replaces the earlier value, as `DuplicateKeys::LastWins`
+    // does.
+    fn visit_map<A: de::MapAccess<'de>>(self, access: A) -> Result<Map, A::Error> {
+        let mut map = Map::new();
+        insert_members(&mut map, access)?;
+        Ok(map)
+    }
+}
+
+fn insert_members<'de, A: de::MapAccess<'de>>(map: &mut Map, mut access: A) -> Result<(), A::Error> {
+    while let Some((key, value)) = access.next_entry()? {
+        map.insert(key, value);
+    }
+
+    Ok(())
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Map, D::Error> {
+        deserializer.deserialize_map(MapVisitor)
+    }
+}
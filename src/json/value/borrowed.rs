@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+use super::pointer;
+use super::Map;
+use super::Number;
+use super::Value;
+
+// A `Value` whose strings and keys borrow from the parsed input where they
+// contain no escapes, so reading a document allocates only for containers
+// and escaped text. Objects keep their members in input order in a plain
+// list; lookups scan it, which is cheap for the few fields a caller
+// typically inspects. Equality therefore compares object members in order,
+// unlike `Value`'s.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BorrowedValue<'a> {
+    #[default]
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(Cow<'a, str>),
+    Array(Vec<BorrowedValue<'a>>),
+    Object(Vec<(Cow<'a, str>, BorrowedValue<'a>)>),
+}
+
+impl<'a> BorrowedValue<'a> {
+    // Looks up a member of an object.
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'a>> {
+        match *self {
+            BorrowedValue::Object(ref members) => {
+                members.iter().find(|&(k, _)| k == key).map(|(_, value)| value)
+            }
+            _ => {
+                None
+            }
+        }
+    }
+
+    // Looks up an element of an array.
+    pub fn get_index(&self, index: usize) -> Option<&BorrowedValue<'a>> {
+        match *self {
+            BorrowedValue::Array(ref list) => list.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn pointer(&self, pointer: &str) -> Option<&BorrowedValue<'a>> {
+        let mut target = self;
+        for token in pointer::parse_pointer(pointer).ok()? {
+            target = match *target {
+                BorrowedValue::Object(_) => target.get(&token)?,
+                BorrowedValue::Array(_) => target.get_index(pointer::parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(*self, BorrowedValue::Null)
+    }
+
+    pub fn as_boolean(&self) -> Option<bool> {
+        match *self {
+            BorrowedValue::Boolean(b) => Some(b),
+            _ => None
+        }
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(*self, BorrowedValue::Boolean(_))
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            BorrowedValue::Number(n) => Some(n),
+            _ => None
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(*self, BorrowedValue::Number(_))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedValue::String(str) => Some(str),
+            _ => None
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(*self, BorrowedValue::String(_))
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<BorrowedValue<'a>>> {
+        match self {
+            BorrowedValue::Array(list) => Some(list),
+            _ => None
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(*self, BorrowedValue::Array(_))
+    }
+
+    pub fn as_object(&self) -> Option<&Vec<(Cow<'a, str>, BorrowedValue<'a>)>> {
+        match self {
+            BorrowedValue::Object(members) => Some(members),
+            _ => None
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(*self, BorrowedValue::Object(_))
+    }
+
+    // See `Value::drop_iteratively`.
+    pub fn drop_iteratively(self) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                BorrowedValue::Array(list) => stack.extend(list),
+                BorrowedValue::Object(members) => stack.extend(members.into_iter().map(|(_, value)| value)),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn into_owned(self) -> Value {
+        match self {
+            BorrowedValue::Null => Value::Null,
+            BorrowedValue::Boolean(b) => Value::Boolean(b),
+            BorrowedValue::Number(n) => Value::Number(n),
+            BorrowedValue::String(str) => Value::String(str.into_owned()),
+            BorrowedValue::Array(list) => {
+                Value::Array(list.into_iter().map(BorrowedValue::into_owned).collect())
+            }
+            BorrowedValue::Object(members) => {
+                let map: Map = members
+                    .into_iter()
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect();
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+impl<'a> From<BorrowedValue<'a>> for Value {
+    fn from(value: BorrowedValue<'a>) -> Value {
+        value.into_owned()
+    }
+}
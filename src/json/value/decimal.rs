@@ -0,0 +1,372 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use super::Num;
+use super::Number;
+
+// The exact value of a number under the `arbitrary_precision` feature:
+// `coefficient` × 10^`exponent`. The coefficient is a string of digits
+// without leading or trailing zeros, or `"0"` for zero, so every value has
+// exactly one representation and zero is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    negative: bool,
+    coefficient: String,
+    exponent: i64,
+}
+
+// Exponents beyond this are clamped. The parser only accepts numbers that
+// are finite as `f64`, so a clamped exponent belongs to a value that
+// rounded to zero, and compares correctly anyway.
+const MAX_EXPONENT: i64 = 1 << 48;
+
+// The most digits the arithmetic below writes out. Adding numbers of very
+// different magnitude needs every digit in between, so without a bound
+// `1e-100000 + 1` would be a hundred-thousand-digit number.
+pub const MAX_DIGITS: i64 = 1 << 14;
+
+impl Decimal {
+    // Reads text following the JSON number grammar.
+    pub(crate) fn parse(text: &str) -> Option<Decimal> {
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(index) => (&text[..index], parse_exponent(&text[index + 1..])?),
+            None => (text, 0),
+        };
+        let (whole, fraction) = match mantissa.find('.') {
+            Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+            None => (mantissa, ""),
+        };
+        if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return None
+        }
+
+        let digits = format!("{}{}", whole, fraction);
+        let exponent = exponent - fraction.len() as i64;
+        let trimmed = digits.trim_start_matches('0');
+        let coefficient = trimmed.trim_end_matches('0');
+        if coefficient.is_empty() {
+            return Some(Decimal {
+                negative: false,
+                coefficient: String::from("0"),
+                exponent: 0,
+            })
+        }
+
+        Some(Decimal {
+            negative,
+            coefficient: String::from(coefficient),
+            exponent: exponent + (trimmed.len() - coefficient.len()) as i64,
+        })
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient == "0"
+    }
+
+    pub fn coefficient(&self) -> &str {
+        &self.coefficient
+    }
+
+    pub fn exponent(&self) -> i64 {
+        self.exponent
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.exponent >= 0
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative {
+            return None
+        }
+        self.magnitude()
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude()?;
+        if !self.negative {
+            return i128::try_from(magnitude).ok()
+        }
+        // `i128::MIN` has no positive counterpart, so negate after the cast.
+        match magnitude.cmp(&(1 << 127)) {
+            Ordering::Greater => None,
+            _ => Some((magnitude as i128).wrapping_neg()),
+        }
+    }
+
+    fn magnitude(&self) -> Option<u128> {
+        if !self.is_integer() {
+            return None
+        }
+        let mut n: u128 = 0;
+        for b in self.coefficient.bytes() {
+            n = n.checked_mul(10)?.checked_add(u128::from(b - b'0'))?;
+        }
+        // Overflows within 39 steps unless the value is zero.
+        for _ in 0..self.exponent {
+            n = n.checked_mul(10)?;
+        }
+
+        Some(n)
+    }
+
+    // The power of ten of the leading digit, plus one.
+    fn magnitude_order(&self) -> i64 {
+        self.exponent + self.coefficient.len() as i64
+    }
+
+    // The exact sum, or `None` if it needs more than `MAX_DIGITS` digits.
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        if self.is_zero() {
+            return Some(other.clone())
+        }
+        if other.is_zero() {
+            return Some(self.clone())
+        }
+        let exponent = self.exponent.min(other.exponent);
+        if self.magnitude_order().max(other.magnitude_order()) - exponent >= MAX_DIGITS {
+            return None
+        }
+        let (a, b) = (self.digits(exponent), other.digits(exponent));
+        let (negative, digits) = if self.negative == other.negative {
+            (self.negative, add_digits(&a, &b))
+        } else if compare_digits(&a, &b) == Ordering::Less {
+            (other.negative, sub_digits(&b, &a))
+        } else {
+            (self.negative, sub_digits(&a, &b))
+        };
+
+        Some(Decimal::from_digits(negative, digits, exponent))
+    }
+
+    pub fn checked_sub(&self, other: &Decimal) -> Option<Decimal> {
+        self.checked_add(&Decimal {
+            negative: !other.negative && !other.is_zero(),
+            ..other.clone()
+        })
+    }
+
+    // The exact product, or `None` if it needs more than `MAX_DIGITS` digits.
+    pub fn checked_mul(&self, other: &Decimal) -> Option<Decimal> {
+        if self.coefficient.len() + other.coefficient.len() > MAX_DIGITS as usize {
+            return None
+        }
+        let (a, b) = (self.digits(self.exponent), other.digits(other.exponent));
+        let mut product = vec![0u32; a.len() + b.len()];
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &y) in b.iter().enumerate() {
+                let n = product[i + j] + u32::from(x) * u32::from(y) + carry;
+                product[i + j] = n % 10;
+                carry = n / 10;
+            }
+            product[i + b.len()] += carry;
+        }
+        let digits = product.into_iter().map(|d| d as u8).collect();
+
+        Some(Decimal::from_digits(self.negative != other.negative, digits, self.exponent.saturating_add(other.exponent)))
+    }
+
+    // The coefficient scaled to `exponent`, which is at most the number's
+    // own, as digit values with the least significant first.
+    fn digits(&self, exponent: i64) -> Vec<u8> {
+        let mut digits = vec![0; (self.exponent - exponent) as usize];
+        digits.extend(self.coefficient.bytes().rev().map(|b| b - b'0'));
+        digits
+    }
+
+    // Builds a normalized decimal from least-significant-first digits.
+    fn from_digits(negative: bool, digits: Vec<u8>, exponent: i64) -> Decimal {
+        let low = digits.iter().take_while(|&&d| d == 0).count();
+        let coefficient: String = digits[low..]
+            .iter()
+            .rev()
+            .skip_while(|&&d| d == 0)
+            .map(|&d| char::from(b'0' + d))
+            .collect();
+        if coefficient.is_empty() {
+            return Decimal {
+                negative: false,
+                coefficient: String::from("0"),
+                exponent: 0,
+            }
+        }
+
+        Decimal {
+            negative,
+            coefficient,
+            exponent: exponent.saturating_add(low as i64),
+        }
+    }
+}
+
+// Digit strings here are least significant first, without a sign.
+
+fn add_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let n = a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0) + carry;
+        sum.push(n % 10);
+        carry = n / 10;
+    }
+    sum.push(carry);
+    sum
+}
+
+// `a - b`, where `a` is not the smaller.
+fn sub_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &x) in a.iter().enumerate() {
+        let y = b.get(i).unwrap_or(&0) + borrow;
+        if x >= y {
+            difference.push(x - y);
+            borrow = 0;
+        } else {
+            difference.push(x + 10 - y);
+            borrow = 1;
+        }
+    }
+    difference
+}
+
+fn compare_digits(a: &[u8], b: &[u8]) -> Ordering {
+    let significant = |digits: &[u8]| digits.len() - digits.iter().rev().take_while(|&&d| d == 0).count();
+    let (x, y) = (&a[..significant(a)], &b[..significant(b)]);
+    x.len().cmp(&y.len()).then_with(|| x.iter().rev().cmp(y.iter().rev()))
+}
+
+fn parse_exponent(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+    let mut exponent: i64 = 0;
+    for b in digits.bytes() {
+        exponent = (exponent * 10 + i64::from(b - b'0')).min(MAX_EXPONENT);
+    }
+
+    Some(if negative { -exponent } else { exponent })
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let sign = |d: &Decimal| match (d.negative, d.is_zero()) {
+            (true, _) => -1,
+            (false, true) => 0,
+            (false, false) => 1,
+        };
+        let magnitude = self
+            .magnitude_order()
+            .cmp(&other.magnitude_order())
+            // With the leading digits at the same power of ten, the digit
+            // strings compare like the values; a prefix is the smaller.
+            .then_with(|| self.coefficient.cmp(&other.coefficient));
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if self.negative => magnitude.reverse(),
+            Ordering::Equal => magnitude,
+            ordering => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Plain decimal notation, such as `-0.015` or `1200`, falling back to
+// `15e-300` style once that would mean writing a long run of zeros.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        let digits = self.coefficient.len() as i64;
+        if (0..=64).contains(&self.exponent) {
+            f.write_str(&self.coefficient)?;
+            for _ in 0..self.exponent {
+                f.write_str("0")?;
+            }
+            Ok(())
+        } else if self.exponent < 0 && -self.exponent < digits {
+            let (whole, fraction) = self.coefficient.split_at((digits + self.exponent) as usize);
+            write!(f, "{}.{}", whole, fraction)
+        } else if self.exponent < 0 && -self.exponent - digits <= 64 {
+            f.write_str("0.")?;
+            for _ in 0..-self.exponent - digits {
+                f.write_str("0")?;
+            }
+            f.write_str(&self.coefficient)
+        } else {
+            write!(f, "{}e{}", self.coefficient, self.exponent)
+        }
+    }
+}
+
+// Under `arbitrary_precision` numbers compare by the exact value of their
+// text. For a number built from a float that is the float's shortest
+// round-tripping form, so `Number::from(0.1)` equals the parsed `0.1`. NaN
+// and the infinities, which have no text of their own, keep their place at
+// the ends of the order.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Key {
+    NegInfinity,
+    Finite(Decimal),
+    Infinity,
+    NaN,
+}
+
+impl Number {
+    fn key(&self) -> Key {
+        match self.num {
+            Num::Float(n) if n.is_nan() => Key::NaN,
+            Num::Float(n) if n == f64::INFINITY => Key::Infinity,
+            Num::Float(n) if n == f64::NEG_INFINITY => Key::NegInfinity,
+            _ => Key::Finite(self.to_decimal().expect("finite numbers have decimal text")),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Number) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
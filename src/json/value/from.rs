@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+
+use super::Map;
+use super::Number;
+use super::Value;
+
+// Conversions from plain Rust values, used by the `json!` macro to turn
+// interpolated expressions into `Value`s.
+
+macro_rules! from_number {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(n: $ty) -> Value {
+                    Value::Number(Number::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_number!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Value {
+        Value::Boolean(b)
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Value {
+        Value::Number(number)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(str: &str) -> Value {
+        Value::String(String::from(str))
+    }
+}
+
+impl From<String> for Value {
+    fn from(str: String) -> Value {
+        Value::String(str)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(str: &String) -> Value {
+        Value::String(str.clone())
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value {
+    fn from(str: Cow<'a, str>) -> Value {
+        Value::String(str.into_owned())
+    }
+}
+
+impl From<Map> for Value {
+    fn from(map: Map) -> Value {
+        Value::Object(map)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(list: Vec<T>) -> Value {
+        Value::Array(list.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    fn from(list: &[T]) -> Value {
+        Value::Array(list.iter().cloned().map(Into::into).collect())
+    }
+}
+
+// `None` becomes `null`.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Value {
+        match option {
+            Some(value) => value.into(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Value {
+        Value::Null
+    }
+}
@@ -0,0 +1,528 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::Value;
+
+#[cfg(feature = "preserve_order")]
+use self::ordered as imp;
+#[cfg(not(feature = "preserve_order"))]
+use std::collections::btree_map as imp;
+
+#[cfg(feature = "preserve_order")]
+type MapImpl = ordered::OrderedMap;
+#[cfg(not(feature = "preserve_order"))]
+type MapImpl = std::collections::BTreeMap<String, Value>;
+
+// Object members in insertion order, or sorted by key when the
+// `preserve_order` feature is disabled.
+#[derive(Clone)]
+pub struct Map {
+    map: MapImpl,
+}
+
+impl Map {
+    pub fn new() -> Map {
+        Map {
+            map: MapImpl::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.map.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.map.get_mut(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.map.contains_key(key)
+    }
+
+    // Inserting an existing key replaces its value but keeps its position.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        self.map.insert(key, value)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.map.remove(key)
+    }
+
+    pub fn entry<S: Into<String>>(&mut self, key: S) -> Entry<'_> {
+        match self.map.entry(key.into()) {
+            imp::Entry::Vacant(vacant) => Entry::Vacant(VacantEntry { vacant }),
+            imp::Entry::Occupied(occupied) => Entry::Occupied(OccupiedEntry { occupied }),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            iter: self.map.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_> {
+        Keys {
+            iter: self.map.keys(),
+        }
+    }
+
+    pub fn values(&self) -> Values<'_> {
+        Values {
+            iter: self.map.values(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        ValuesMut {
+            iter: self.map.values_mut(),
+        }
+    }
+}
+
+impl Default for Map {
+    fn default() -> Map {
+        Map::new()
+    }
+}
+
+// Maps are equal when they hold the same members, in any order.
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Eq for Map {}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<(String, Value)> for Map {
+    fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Map {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, Value)> for Map {
+    fn extend<T: IntoIterator<Item = (String, Value)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.map.insert(key, value);
+        }
+    }
+}
+
+pub enum Entry<'a> {
+    Vacant(VacantEntry<'a>),
+    Occupied(OccupiedEntry<'a>),
+}
+
+pub struct VacantEntry<'a> {
+    vacant: imp::VacantEntry<'a, String, Value>,
+}
+
+pub struct OccupiedEntry<'a> {
+    occupied: imp::OccupiedEntry<'a, String, Value>,
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &String {
+        match *self {
+            Entry::Vacant(ref entry) => entry.key(),
+            Entry::Occupied(ref entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, default: F) -> &'a mut Value {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut Value)>(self, f: F) -> Entry<'a> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    pub fn key(&self) -> &String {
+        self.vacant.key()
+    }
+
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        self.vacant.insert(value)
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn key(&self) -> &String {
+        self.occupied.key()
+    }
+
+    pub fn get(&self) -> &Value {
+        self.occupied.get()
+    }
+
+    pub fn get_mut(&mut self) -> &mut Value {
+        self.occupied.get_mut()
+    }
+
+    pub fn into_mut(self) -> &'a mut Value {
+        self.occupied.into_mut()
+    }
+
+    pub fn insert(&mut self, value: Value) -> Value {
+        self.occupied.insert(value)
+    }
+
+    pub fn remove(self) -> Value {
+        self.occupied.remove()
+    }
+}
+
+macro_rules! delegate_iterator {
+    ($name:ident<$($lt:lifetime)*> => $item:ty) => {
+        impl<$($lt)*> Iterator for $name<$($lt)*> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.iter.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<$($lt)*> DoubleEndedIterator for $name<$($lt)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.iter.next_back()
+            }
+        }
+
+        impl<$($lt)*> ExactSizeIterator for $name<$($lt)*> {
+            fn len(&self) -> usize {
+                self.iter.len()
+            }
+        }
+    };
+}
+
+pub struct Iter<'a> {
+    iter: imp::Iter<'a, String, Value>,
+}
+
+delegate_iterator!(Iter<'a> => (&'a String, &'a Value));
+
+pub struct IterMut<'a> {
+    iter: imp::IterMut<'a, String, Value>,
+}
+
+delegate_iterator!(IterMut<'a> => (&'a String, &'a mut Value));
+
+pub struct IntoIter {
+    iter: imp::IntoIter<String, Value>,
+}
+
+delegate_iterator!(IntoIter<> => (String, Value));
+
+pub struct Keys<'a> {
+    iter: imp::Keys<'a, String, Value>,
+}
+
+delegate_iterator!(Keys<'a> => &'a String);
+
+pub struct Values<'a> {
+    iter: imp::Values<'a, String, Value>,
+}
+
+delegate_iterator!(Values<'a> => &'a Value);
+
+pub struct ValuesMut<'a> {
+    iter: imp::ValuesMut<'a, String, Value>,
+}
+
+delegate_iterator!(ValuesMut<'a> => &'a mut Value);
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a String, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+// A minimal insertion-ordered map mirroring the parts of the `btree_map`
+// API that `Map` delegates to, so both backends plug in the same way.
+#[cfg(feature = "preserve_order")]
+mod ordered {
+    use std::collections::HashMap;
+    use std::iter;
+    use std::marker::PhantomData;
+    use std::mem;
+    use std::slice;
+    use std::vec;
+
+    use super::Value;
+
+    #[derive(Clone)]
+    pub struct OrderedMap {
+        entries: Vec<(String, Value)>,
+        indices: HashMap<String, usize>,
+    }
+
+    impl OrderedMap {
+        pub fn new() -> OrderedMap {
+            OrderedMap {
+                entries: Vec::new(),
+                indices: HashMap::new(),
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn clear(&mut self) {
+            self.entries.clear();
+            self.indices.clear();
+        }
+
+        pub fn get(&self, key: &str) -> Option<&Value> {
+            match self.indices.get(key) {
+                Some(&index) => Some(&self.entries[index].1),
+                None => None,
+            }
+        }
+
+        pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+            match self.indices.get(key) {
+                Some(&index) => Some(&mut self.entries[index].1),
+                None => None,
+            }
+        }
+
+        pub fn contains_key(&self, key: &str) -> bool {
+            self.indices.contains_key(key)
+        }
+
+        pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+            match self.indices.get(&key) {
+                Some(&index) => Some(mem::replace(&mut self.entries[index].1, value)),
+                None => {
+                    self.push(key, value);
+                    None
+                }
+            }
+        }
+
+        pub fn remove(&mut self, key: &str) -> Option<Value> {
+            self.indices.remove(key).map(|index| self.remove_index(index))
+        }
+
+        pub fn entry(&mut self, key: String) -> Entry<'_, String, Value> {
+            match self.indices.get(&key) {
+                Some(&index) => Entry::Occupied(OccupiedEntry {
+                    map: self,
+                    index,
+                    marker: PhantomData,
+                }),
+                None => Entry::Vacant(VacantEntry {
+                    map: self,
+                    key,
+                    marker: PhantomData,
+                }),
+            }
+        }
+
+        pub fn iter(&self) -> Iter<'_, String, Value> {
+            self.entries.iter().map(key_value as _)
+        }
+
+        pub fn iter_mut(&mut self) -> IterMut<'_, String, Value> {
+            self.entries.iter_mut().map(key_value_mut as _)
+        }
+
+        pub fn keys(&self) -> Keys<'_, String, Value> {
+            self.entries.iter().map(key as _)
+        }
+
+        pub fn values(&self) -> Values<'_, String, Value> {
+            self.entries.iter().map(value as _)
+        }
+
+        pub fn values_mut(&mut self) -> ValuesMut<'_, String, Value> {
+            self.entries.iter_mut().map(value_mut as _)
+        }
+
+        fn push(&mut self, key: String, value: Value) -> usize {
+            let index = self.entries.len();
+            self.indices.insert(key.clone(), index);
+            self.entries.push((key, value));
+            index
+        }
+
+        fn remove_index(&mut self, index: usize) -> Value {
+            let (key, value) = self.entries.remove(index);
+            self.indices.remove(&key);
+            for index in self.indices.values_mut().filter(|i| **i > index) {
+                *index -= 1;
+            }
+            value
+        }
+    }
+
+    fn key_value(entry: &(String, Value)) -> (&String, &Value) {
+        (&entry.0, &entry.1)
+    }
+
+    fn key_value_mut(entry: &mut (String, Value)) -> (&String, &mut Value) {
+        (&entry.0, &mut entry.1)
+    }
+
+    fn key(entry: &(String, Value)) -> &String {
+        &entry.0
+    }
+
+    fn value(entry: &(String, Value)) -> &Value {
+        &entry.1
+    }
+
+    fn value_mut(entry: &mut (String, Value)) -> &mut Value {
+        &mut entry.1
+    }
+
+    impl IntoIterator for OrderedMap {
+        type Item = (String, Value);
+        type IntoIter = vec::IntoIter<(String, Value)>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.entries.into_iter()
+        }
+    }
+
+    // The key and value type parameters only exist so these aliases line up
+    // with their `btree_map` counterparts.
+    pub type Iter<'a, K, V> = iter::Map<slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
+    pub type IterMut<'a, K, V> = iter::Map<slice::IterMut<'a, (K, V)>, fn(&'a mut (K, V)) -> (&'a K, &'a mut V)>;
+    pub type IntoIter<K, V> = vec::IntoIter<(K, V)>;
+    pub type Keys<'a, K, V> = iter::Map<slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> &'a K>;
+    pub type Values<'a, K, V> = iter::Map<slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> &'a V>;
+    pub type ValuesMut<'a, K, V> = iter::Map<slice::IterMut<'a, (K, V)>, fn(&'a mut (K, V)) -> &'a mut V>;
+
+    pub enum Entry<'a, K, V> {
+        Vacant(VacantEntry<'a, K, V>),
+        Occupied(OccupiedEntry<'a, K, V>),
+    }
+
+    pub struct VacantEntry<'a, K, V> {
+        map: &'a mut OrderedMap,
+        key: String,
+        marker: PhantomData<(K, V)>,
+    }
+
+    pub struct OccupiedEntry<'a, K, V> {
+        map: &'a mut OrderedMap,
+        index: usize,
+        marker: PhantomData<(K, V)>,
+    }
+
+    impl<'a> VacantEntry<'a, String, Value> {
+        pub fn key(&self) -> &String {
+            &self.key
+        }
+
+        pub fn insert(self, value: Value) -> &'a mut Value {
+            let index = self.map.push(self.key, value);
+            &mut self.map.entries[index].1
+        }
+    }
+
+    impl<'a> OccupiedEntry<'a, String, Value> {
+        pub fn key(&self) -> &String {
+            &self.map.entries[self.index].0
+        }
+
+        pub fn get(&self) -> &Value {
+            &self.map.entries[self.index].1
+        }
+
+        pub fn get_mut(&mut self) -> &mut Value {
+            &mut self.map.entries[self.index].1
+        }
+
+        pub fn into_mut(self) -> &'a mut Value {
+            &mut self.map.entries[self.index].1
+        }
+
+        pub fn insert(&mut self, value: Value) -> Value {
+            mem::replace(self.get_mut(), value)
+        }
+
+        pub fn remove(self) -> Value {
+            self.map.remove_index(self.index)
+        }
+    }
+}
@@ -1,19 +1,39 @@
-use std::collections::HashMap;
+use std::fmt;
+
+use super::ser;
 
 pub mod number;
 pub mod index;
+pub mod map;
+pub mod pointer;
+pub mod borrowed;
+pub mod raw;
+mod from;
+#[cfg(feature = "arbitrary_precision")]
+pub mod decimal;
 
 pub use number::Number;
 pub use number::Num;
+pub use number::NumberError;
+#[cfg(feature = "arbitrary_precision")]
+pub use decimal::Decimal;
 pub use index::Index;
+pub use map::Map;
+pub use pointer::PointerError;
+pub use borrowed::BorrowedValue;
+pub use raw::RawValue;
 
+#[derive(Debug, Clone, Default)]
 pub enum Value {
+    #[default]
     Null,
     Boolean(bool),
     Number(Number),
     String(String),
     Array(Vec<Value>),
-    Object(HashMap<String, Value>),
+    Object(Map),
+    // A subtree kept as its original text; see `De::raw_pointer`.
+    Raw(RawValue),
 }
 
 impl Value {
@@ -21,11 +41,20 @@ impl Value {
         i.index_into(self)
     }
 
+    pub fn index_into_mut<I: Index>(&mut self, i: I) -> Option<&mut Value> {
+        i.index_into_mut(self)
+    }
+
+    pub fn get<I: Index>(&self, i: I) -> Option<&Value> {
+        i.index_into(self)
+    }
+
+    pub fn get_mut<I: Index>(&mut self, i: I) -> Option<&mut Value> {
+        i.index_into_mut(self)
+    }
+
     pub fn is_null(&self) -> bool {
-        match *self {
-            Value::Null => true,
-            _ => false
-        }
+        matches!(*self, Value::Null)
     }
 
     pub fn as_boolean(&self) -> Option<bool> {
@@ -35,13 +64,17 @@ impl Value {
         }
     }
 
-    pub fn is_boolean(&self) -> bool {
+    pub fn as_boolean_mut(&mut self) -> Option<&mut bool> {
         match *self {
-            Value::Boolean(_) => true,
-            _ => false
+            Value::Boolean(ref mut b) => Some(b),
+            _ => None
         }
     }
 
+    pub fn is_boolean(&self) -> bool {
+        matches!(*self, Value::Boolean(_))
+    }
+
     pub fn as_number(&self) -> Option<&Number> {
         match self {
             Value::Number(n) => Some(n),
@@ -49,13 +82,17 @@ impl Value {
         }
     }
 
-    pub fn is_number(&self) -> bool {
-        match *self {
-            Value::Number(_) => true,
-            _ => false
+    pub fn as_number_mut(&mut self) -> Option<&mut Number> {
+        match self {
+            Value::Number(n) => Some(n),
+            _ => None
         }
     }
 
+    pub fn is_number(&self) -> bool {
+        matches!(*self, Value::Number(_))
+    }
+
     pub fn as_str(&self) -> Option<&str> {
         match *self {
             Value::String(ref s) => Some(s),
@@ -63,38 +100,103 @@ impl Value {
         }
     }
 
-    pub fn is_string(&self) -> bool {
+    pub fn as_str_mut(&mut self) -> Option<&mut String> {
         match *self {
-            Value::String(_) => true,
-            _ => false
+            Value::String(ref mut s) => Some(s),
+            _ => None
         }
     }
 
+    pub fn is_string(&self) -> bool {
+        matches!(*self, Value::String(_))
+    }
+
     pub fn as_array(&self) -> Option<&Vec<Value>> {
         match *self {
-            Value::Array(ref array) => Some(&*array),
+            Value::Array(ref array) => Some(array),
             _ => None,
         }
     }
 
-    pub fn is_array(&self) -> bool {
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
         match *self {
-            Value::Array(_) => true,
-            _ => false,
+            Value::Array(ref mut array) => Some(array),
+            _ => None,
         }
     }
 
-    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+    pub fn is_array(&self) -> bool {
+        matches!(*self, Value::Array(_))
+    }
+
+    pub fn as_object(&self) -> Option<&Map> {
         match *self {
             Value::Object(ref map) => Some(map),
             _ => None,
         }
     }
 
-    pub fn is_object(&self) -> bool {
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
         match *self {
-            Value::Object(_) => true,
+            Value::Object(ref mut map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(*self, Value::Object(_))
+    }
+
+    // Drops the value without recursing. A plain drop recurses once per
+    // level of nesting, which a document parsed with
+    // `ParserOptions::explicit_stack` may have more of than the call stack
+    // allows.
+    pub fn drop_iteratively(self) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Array(list) => stack.extend(list),
+                Value::Object(map) => stack.extend(map.into_iter().map(|(_, value)| value)),
+                _ => {}
+            }
+        }
+    }
+}
+
+// Numbers are equal by value (see `Number`), objects regardless of member
+// order, and raw text by what it parses to, so `Raw("[1, 2]")` equals an
+// array of `1` and `2`. Raw values with the same text are equal without
+// parsing it.
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            (Value::Raw(a), Value::Raw(b)) if a == b => true,
+            (Value::Raw(a), b) => a.to_value().is_ok_and(|a| a == *b),
+            (a, Value::Raw(b)) => b.to_value().is_ok_and(|b| *a == b),
             _ => false,
         }
     }
 }
+
+impl Eq for Value {}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let str = if f.alternate() {
+            ser::to_string_pretty(self)
+        } else {
+            ser::to_string(self)
+        };
+        match str {
+            Ok(str) => f.write_str(&str),
+            Err(_) => Err(fmt::Error),
+        }
+    }
+}
+
@@ -1,9 +1,493 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::str::FromStr;
+
+use super::super::de::Error;
+use super::super::de::parser;
+#[cfg(feature = "arbitrary_precision")]
+use super::decimal::Decimal;
+
+// Numbers compare by their mathematical value, whichever variant holds
+// them: `PosInt(1)` equals `Float(1.0)` and `Float(0.0)` equals
+// `Float(-0.0)`. Integers and floats are compared exactly, not by
+// converting the integer to a float, so `2^53 + 1` does not equal `2^53` as
+// a float. To make this a total order, all NaNs are equal to each other and
+// greater than every other number, including infinity. Hashing agrees with
+// equality, so numbers can be used as map keys.
+//
+// With the `arbitrary_precision` feature a number also keeps its text as
+// parsed, and `num` is only the nearest value of the primitive types. The
+// text is what gets serialized and compared, so build numbers with
+// `Number::from` rather than by setting `num`.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "arbitrary_precision"), derive(PartialEq, Eq, PartialOrd, Ord, Hash))]
 pub struct Number {
-    pub num: Num
+    pub num: Num,
+    #[cfg(feature = "arbitrary_precision")]
+    text: Box<str>,
 }
 
+#[derive(Clone, Copy, Debug)]
 pub enum Num {
     PosInt(u64),
     NegInt(i64),
     Float(f64),
 }
+
+// Why a checked conversion such as `Number::to_u8` failed. Each variant
+// names the type converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+    // The value lies outside the range of the type, or is not finite.
+    OutOfRange(&'static str),
+    // The value is a float with a fractional part, which an integer type
+    // cannot hold.
+    Fractional(&'static str),
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NumberError::OutOfRange(ty) => write!(f, "number out of range for {}", ty),
+            NumberError::Fractional(ty) => write!(f, "number with a fractional part cannot be converted to {}", ty),
+        }
+    }
+}
+
+impl error::Error for NumberError {}
+
+// An integral value widened to 128 bits, for the checked conversions to
+// narrow from.
+pub(crate) enum Integer {
+    Unsigned(u128),
+    Signed(i128),
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+macro_rules! checked_conversions {
+    ($($name:ident => $ty:ty,)*) => {
+        $(
+            // Converts to the type without losing anything; a float with
+            // an integral value, such as `3.0`, converts like the integer.
+            pub fn $name(&self) -> Result<$ty, NumberError> {
+                let converted = match self.integer(stringify!($ty))? {
+                    Integer::Unsigned(n) => <$ty>::try_from(n).ok(),
+                    Integer::Signed(n) => <$ty>::try_from(n).ok(),
+                };
+                converted.ok_or(NumberError::OutOfRange(stringify!($ty)))
+            }
+        )*
+    };
+}
+
+impl Number {
+    // The value as a `u64`, if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.num {
+            Num::PosInt(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    // The value as an `i64`, if it is an integer in its range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.num {
+            Num::PosInt(n) => i64::try_from(n).ok(),
+            Num::NegInt(n) => Some(n),
+            Num::Float(_) => None,
+        }
+    }
+
+    // The value as an `f64`, rounding integers beyond 2^53 to the nearest
+    // float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.num {
+            Num::PosInt(n) => Some(n as f64),
+            Num::NegInt(n) => Some(n as f64),
+            Num::Float(n) => Some(n),
+        }
+    }
+
+    pub fn is_u64(&self) -> bool {
+        matches!(self.num, Num::PosInt(_))
+    }
+
+    pub fn is_i64(&self) -> bool {
+        match self.num {
+            Num::PosInt(n) => i64::try_from(n).is_ok(),
+            Num::NegInt(_) => true,
+            Num::Float(_) => false,
+        }
+    }
+
+    pub fn is_f64(&self) -> bool {
+        matches!(self.num, Num::Float(_))
+    }
+
+    // A number parsed from `text`, which the `arbitrary_precision` feature
+    // keeps.
+    #[cfg_attr(not(feature = "arbitrary_precision"), allow(unused_variables))]
+    pub(crate) fn parsed(num: Num, text: &str) -> Number {
+        Number {
+            num,
+            #[cfg(feature = "arbitrary_precision")]
+            text: Box::from(text),
+        }
+    }
+
+    // The number exactly as parsed, or as formatted when it was built from
+    // a primitive.
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    // The exact value as a decimal, or `None` for NaN and infinities.
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self.num {
+            Num::Float(n) if !n.is_finite() => None,
+            _ => Decimal::parse(&self.text),
+        }
+    }
+
+    // Whether the number is written as an integer, without a fraction or
+    // exponent, however large it is.
+    pub(crate) fn is_integer(&self) -> bool {
+        #[cfg(feature = "arbitrary_precision")]
+        {
+            self.text.bytes().all(|b| b == b'-' || b.is_ascii_digit())
+        }
+        #[cfg(not(feature = "arbitrary_precision"))]
+        {
+            !self.is_f64()
+        }
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    pub(crate) fn integer(&self, ty: &'static str) -> Result<Integer, NumberError> {
+        match self.num {
+            Num::PosInt(n) => Ok(Integer::Unsigned(u128::from(n))),
+            Num::NegInt(n) => Ok(Integer::Signed(i128::from(n))),
+            Num::Float(n) if n.is_finite() && n.fract() != 0.0 => Err(NumberError::Fractional(ty)),
+            // Within these bounds the conversions below are exact.
+            Num::Float(n) if (0.0..TWO_128).contains(&n) => Ok(Integer::Unsigned(n as u128)),
+            Num::Float(n) if (-TWO_127..0.0).contains(&n) => Ok(Integer::Signed(n as i128)),
+            Num::Float(_) => Err(NumberError::OutOfRange(ty)),
+        }
+    }
+
+    // Reads the text, so integers beyond 64 bits convert exactly.
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) fn integer(&self, ty: &'static str) -> Result<Integer, NumberError> {
+        let decimal = self.to_decimal().ok_or(NumberError::OutOfRange(ty))?;
+        if !decimal.is_integer() {
+            return Err(NumberError::Fractional(ty))
+        }
+        let converted = if decimal.is_negative() {
+            decimal.to_i128().map(Integer::Signed)
+        } else {
+            decimal.to_u128().map(Integer::Unsigned)
+        };
+        converted.ok_or(NumberError::OutOfRange(ty))
+    }
+
+    // Arithmetic that does not round where the operands are exact: integers
+    // stay integers, and `None` means the result leaves the range of the
+    // integer variants. With a float involved the operation is done on
+    // floats, rounding as they do, and `None` means a result that is not
+    // finite. Under `arbitrary_precision` every finite number is exact and
+    // the result is too, with `None` only for results that are not finite
+    // as an `f64` or need more than `decimal::MAX_DIGITS` digits.
+    pub fn checked_add(&self, other: &Number) -> Option<Number> {
+        self.checked(other, Op::Add)
+    }
+
+    pub fn checked_sub(&self, other: &Number) -> Option<Number> {
+        self.checked(other, Op::Sub)
+    }
+
+    pub fn checked_mul(&self, other: &Number) -> Option<Number> {
+        self.checked(other, Op::Mul)
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn checked(&self, other: &Number, op: Op) -> Option<Number> {
+        if self.is_f64() || other.is_f64() {
+            let (a, b) = (self.as_f64()?, other.as_f64()?);
+            let n = match op {
+                Op::Add => a + b,
+                Op::Sub => a - b,
+                Op::Mul => a * b,
+            };
+            return Some(Number::from(n)).filter(|_| n.is_finite())
+        }
+        let (a, b) = (integer(self.num), integer(other.num));
+        let n = match op {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+        }?;
+        if u64::try_from(n).is_err() && i64::try_from(n).is_err() {
+            return None
+        }
+
+        Some(Number::from(n))
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn checked(&self, other: &Number, op: Op) -> Option<Number> {
+        let (a, b) = (self.to_decimal()?, other.to_decimal()?);
+        let n = match op {
+            Op::Add => a.checked_add(&b),
+            Op::Sub => a.checked_sub(&b),
+            Op::Mul => a.checked_mul(&b),
+        }?;
+        parser::convert_number(&n.to_string(), !n.is_integer())
+    }
+
+    checked_conversions! {
+        to_u8 => u8,
+        to_u16 => u16,
+        to_u32 => u32,
+        to_u64 => u64,
+        to_u128 => u128,
+        to_usize => usize,
+        to_i8 => i8,
+        to_i16 => i16,
+        to_i32 => i32,
+        to_i64 => i64,
+        to_i128 => i128,
+        to_isize => isize,
+    }
+}
+
+// Accepts exactly the JSON number grammar: no leading `+`, no leading
+// zeros, no surrounding whitespace.
+impl FromStr for Number {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Number, Error> {
+        parser::parse_number(str)
+    }
+}
+
+impl fmt::Debug for Number {
+    #[cfg(feature = "arbitrary_precision")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Number({})", self.text)
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.num {
+            Num::PosInt(n) => write!(f, "Number({})", n),
+            Num::NegInt(n) => write!(f, "Number({})", n),
+            Num::Float(n) => write!(f, "Number({:?})", n),
+        }
+    }
+}
+
+impl Default for Number {
+    fn default() -> Number {
+        Number::from(Num::default())
+    }
+}
+
+impl From<Num> for Number {
+    fn from(num: Num) -> Number {
+        Number {
+            num,
+            #[cfg(feature = "arbitrary_precision")]
+            text: match num {
+                Num::PosInt(n) => n.to_string().into(),
+                Num::NegInt(n) => n.to_string().into(),
+                Num::Float(n) => format!("{:?}", n).into(),
+            },
+        }
+    }
+}
+
+impl Default for Num {
+    fn default() -> Num {
+        Num::PosInt(0)
+    }
+}
+
+impl PartialEq for Num {
+    fn eq(&self, other: &Num) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Num {}
+
+impl PartialOrd for Num {
+    fn partial_cmp(&self, other: &Num) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Num {
+    fn cmp(&self, other: &Num) -> Ordering {
+        match (*self, *other) {
+            (Num::Float(a), Num::Float(b)) => compare_floats(a, b),
+            (Num::Float(a), b) => compare_integer(integer(b), a).reverse(),
+            (a, Num::Float(b)) => compare_integer(integer(a), b),
+            (a, b) => integer(a).cmp(&integer(b)),
+        }
+    }
+}
+
+// Integers, and floats holding an integer in their range, hash as that
+// integer; other floats hash their bits, with every NaN hashing the same.
+impl Hash for Num {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Num::Float(n) if n.is_nan() => {
+                f64::NAN.to_bits().hash(state);
+            }
+            Num::Float(n) if n.fract() == 0.0 && (-TWO_63..TWO_64).contains(&n) => {
+                (n as i128).hash(state);
+            }
+            Num::Float(n) => {
+                n.to_bits().hash(state);
+            }
+            n => {
+                integer(n).hash(state);
+            }
+        }
+    }
+}
+
+// The bounds of the integer variants, `i64::MIN` and `u64::MAX + 1`, which
+// are exact as floats.
+const TWO_63: f64 = 9_223_372_036_854_775_808.0;
+const TWO_64: f64 = 18_446_744_073_709_551_616.0;
+#[cfg(not(feature = "arbitrary_precision"))]
+const TWO_127: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+#[cfg(not(feature = "arbitrary_precision"))]
+const TWO_128: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+
+fn integer(num: Num) -> i128 {
+    match num {
+        Num::PosInt(n) => i128::from(n),
+        Num::NegInt(n) => i128::from(n),
+        Num::Float(_) => unreachable!("not an integer"),
+    }
+}
+
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).expect("neither is NaN"),
+    }
+}
+
+fn compare_integer(a: i128, b: f64) -> Ordering {
+    if b.is_nan() || b >= TWO_64 {
+        return Ordering::Less
+    }
+    if b < -TWO_63 {
+        return Ordering::Greater
+    }
+    // Within these bounds the integer part of `b` fits an `i128` exactly.
+    let whole = b.trunc();
+    match a.cmp(&(whole as i128)) {
+        Ordering::Equal => {
+            0.0.partial_cmp(&(b - whole)).expect("neither is NaN")
+        }
+        ordering => {
+            ordering
+        }
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Number {
+                    Number::from(Num::PosInt(n as u64))
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8 u16 u32 u64 usize);
+
+macro_rules! from_signed {
+    ($($ty:ty)*) => {
+        $(
+            // Non-negative values are stored as `PosInt`, as the parser does.
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Number {
+                    let num = if n < 0 {
+                        Num::NegInt(n as i64)
+                    } else {
+                        Num::PosInt(n as u64)
+                    };
+                    Number::from(num)
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8 i16 i32 i64 isize);
+
+macro_rules! from_wide {
+    ($($ty:ty)*) => {
+        $(
+            // Values outside the range of the integer variants become the
+            // nearest float, though `arbitrary_precision` keeps them exact.
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Number {
+                    let num = if let Ok(n) = u64::try_from(n) {
+                        Num::PosInt(n)
+                    } else if let Ok(n) = i64::try_from(n) {
+                        Num::NegInt(n)
+                    } else {
+                        Num::Float(n as f64)
+                    };
+                    #[allow(unused_mut)]
+                    let mut number = Number::from(num);
+                    #[cfg(feature = "arbitrary_precision")]
+                    {
+                        number.text = n.to_string().into();
+                    }
+                    number
+                }
+            }
+        )*
+    };
+}
+
+from_wide!(i128 u128);
+
+macro_rules! from_float {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(n: $ty) -> Number {
+                    Number::from(Num::Float(f64::from(n)))
+                }
+            }
+        )*
+    };
+}
+
+from_float!(f32 f64);
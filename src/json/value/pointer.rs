@@ -0,0 +1,220 @@
+use std::error;
+use std::fmt;
+use std::mem;
+
+use super::Value;
+
+// JSON Pointer (RFC 6901) support.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    // A non-empty pointer that does not start with '/'.
+    Syntax,
+    // A '~' not followed by '0' or '1'.
+    InvalidEscape,
+    // A reference token that is not a valid index for the array it is applied to.
+    InvalidIndex(String),
+    // A reference token that names nothing in the document.
+    NotFound(String),
+    // A reference token applied to something other than an object or array.
+    NotContainer(String),
+    // The whole document cannot be removed.
+    Root,
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PointerError::Syntax => f.write_str("JSON pointer must be empty or start with '/'"),
+            PointerError::InvalidEscape => f.write_str("'~' must be followed by '0' or '1' in JSON pointer"),
+            PointerError::InvalidIndex(ref token) => write!(f, "invalid array index {:?}", token),
+            PointerError::NotFound(ref token) => write!(f, "{:?} not found", token),
+            PointerError::NotContainer(ref token) => write!(f, "cannot look up {:?} in a scalar", token),
+            PointerError::Root => f.write_str("cannot remove the root of the document"),
+        }
+    }
+}
+
+impl error::Error for PointerError {}
+
+pub fn parse_pointer(pointer: &str) -> Result<Vec<String>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new())
+    }
+    if !pointer.starts_with('/') {
+        return Err(PointerError::Syntax)
+    }
+
+    pointer[1..].split('/').map(unescape_token).collect()
+}
+
+pub fn unescape_token(token: &str) -> Result<String, PointerError> {
+    let mut str = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => {
+                match chars.next() {
+                    Some('0') => str.push('~'),
+                    Some('1') => str.push('/'),
+                    _ => return Err(PointerError::InvalidEscape),
+                }
+            }
+            _ => {
+                str.push(c);
+            }
+        }
+    }
+
+    Ok(str)
+}
+
+pub fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn to_pointer<S: AsRef<str>>(tokens: &[S]) -> String {
+    let mut pointer = String::new();
+    for token in tokens {
+        pointer.push('/');
+        pointer.push_str(&escape_token(token.as_ref()));
+    }
+    pointer
+}
+
+// Array indices are either "0" or have no leading zeros; "-" is handled by
+// the callers that accept it.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None
+    }
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+    token.parse().ok()
+}
+
+fn step<'v>(value: &'v Value, token: &str) -> Result<&'v Value, PointerError> {
+    match *value {
+        Value::Object(ref map) => {
+            map.get(token).ok_or_else(|| PointerError::NotFound(String::from(token)))
+        }
+        Value::Array(ref list) => {
+            match parse_index(token) {
+                Some(index) => list.get(index).ok_or_else(|| PointerError::NotFound(String::from(token))),
+                None => Err(PointerError::InvalidIndex(String::from(token))),
+            }
+        }
+        _ => {
+            Err(PointerError::NotContainer(String::from(token)))
+        }
+    }
+}
+
+fn step_mut<'v>(value: &'v mut Value, token: &str) -> Result<&'v mut Value, PointerError> {
+    match *value {
+        Value::Object(ref mut map) => {
+            map.get_mut(token).ok_or_else(|| PointerError::NotFound(String::from(token)))
+        }
+        Value::Array(ref mut list) => {
+            match parse_index(token) {
+                Some(index) => list.get_mut(index).ok_or_else(|| PointerError::NotFound(String::from(token))),
+                None => Err(PointerError::InvalidIndex(String::from(token))),
+            }
+        }
+        _ => {
+            Err(PointerError::NotContainer(String::from(token)))
+        }
+    }
+}
+
+pub fn resolve<'v, S: AsRef<str>>(value: &'v Value, tokens: &[S]) -> Result<&'v Value, PointerError> {
+    let mut target = value;
+    for token in tokens {
+        target = step(target, token.as_ref())?;
+    }
+    Ok(target)
+}
+
+pub fn resolve_mut<'v, S: AsRef<str>>(value: &'v mut Value, tokens: &[S]) -> Result<&'v mut Value, PointerError> {
+    let mut target = value;
+    for token in tokens {
+        target = step_mut(target, token.as_ref())?;
+    }
+    Ok(target)
+}
+
+// Adds `value` at the location named by `tokens`, following the semantics of
+// the JSON Patch "add" operation: members are inserted or replaced, array
+// elements are inserted before the index (or appended for "-"), and the
+// empty pointer replaces the whole document.
+pub fn insert<S: AsRef<str>>(root: &mut Value, tokens: &[S], value: Value) -> Result<Option<Value>, PointerError> {
+    let (last, parent) = match tokens.split_last() {
+        Some((last, parent)) => (last.as_ref(), parent),
+        None => return Ok(Some(mem::replace(root, value))),
+    };
+    match *resolve_mut(root, parent)? {
+        Value::Object(ref mut map) => {
+            Ok(map.insert(String::from(last), value))
+        }
+        Value::Array(ref mut list) => {
+            let index = if last == "-" {
+                list.len()
+            } else {
+                match parse_index(last) {
+                    Some(index) if index <= list.len() => index,
+                    _ => return Err(PointerError::InvalidIndex(String::from(last))),
+                }
+            };
+            list.insert(index, value);
+            Ok(None)
+        }
+        _ => {
+            Err(PointerError::NotContainer(String::from(last)))
+        }
+    }
+}
+
+pub fn remove<S: AsRef<str>>(root: &mut Value, tokens: &[S]) -> Result<Value, PointerError> {
+    let (last, parent) = match tokens.split_last() {
+        Some((last, parent)) => (last.as_ref(), parent),
+        None => return Err(PointerError::Root),
+    };
+    match *resolve_mut(root, parent)? {
+        Value::Object(ref mut map) => {
+            map.remove(last).ok_or_else(|| PointerError::NotFound(String::from(last)))
+        }
+        Value::Array(ref mut list) => {
+            match parse_index(last) {
+                Some(index) if index < list.len() => Ok(list.remove(index)),
+                Some(_) => Err(PointerError::NotFound(String::from(last))),
+                None => Err(PointerError::InvalidIndex(String::from(last))),
+            }
+        }
+        _ => {
+            Err(PointerError::NotContainer(String::from(last)))
+        }
+    }
+}
+
+impl Value {
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        let tokens = parse_pointer(pointer).ok()?;
+        resolve(self, &tokens).ok()
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let tokens = parse_pointer(pointer).ok()?;
+        resolve_mut(self, &tokens).ok()
+    }
+
+    pub fn pointer_insert(&mut self, pointer: &str, value: Value) -> Result<Option<Value>, PointerError> {
+        let tokens = parse_pointer(pointer)?;
+        insert(self, &tokens, value)
+    }
+
+    pub fn pointer_remove(&mut self, pointer: &str) -> Result<Value, PointerError> {
+        let tokens = parse_pointer(pointer)?;
+        remove(self, &tokens)
+    }
+}
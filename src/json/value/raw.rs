@@ -0,0 +1,63 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::Value;
+use super::super::de;
+use super::super::de::Error;
+use super::super::de::Parser;
+use super::super::de::StrRead;
+
+// The exact text of a JSON value, checked against the grammar but not turned
+// into a `Value`. The serializer writes it out verbatim, so a subtree can be
+// passed through without being rebuilt or reformatted. Two raw values are
+// equal only if their text is; compare the values they hold to ignore
+// formatting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue {
+    json: String,
+}
+
+impl RawValue {
+    // Checks that `json` is a single JSON value. Surrounding whitespace is
+    // kept as given.
+    pub fn from_string(json: String) -> Result<RawValue, Error> {
+        let mut parser = Parser::new(StrRead::new(&json));
+        while parser.next_event()?.is_some() {}
+        Ok(RawValue {
+            json
+        })
+    }
+
+    // For text the parser has already validated.
+    pub(crate) fn from_validated(json: String) -> RawValue {
+        RawValue {
+            json
+        }
+    }
+
+    pub fn get(&self) -> &str {
+        &self.json
+    }
+
+    pub fn into_string(self) -> String {
+        self.json
+    }
+
+    pub fn to_value(&self) -> Result<Value, Error> {
+        de::from_str(&self.json)
+    }
+}
+
+impl FromStr for RawValue {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<RawValue, Error> {
+        RawValue::from_string(String::from(str))
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.json)
+    }
+}
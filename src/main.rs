@@ -1,5 +1,33 @@
-mod json;
+use std::env;
+use std::fs;
+use std::process;
+
+use json_parser::json;
 
 fn main() {
-    println!("Hello, world!");
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: json_parser <file>");
+            process::exit(2);
+        }
+    };
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("error: {}: {}", path, err);
+            process::exit(2);
+        }
+    };
+
+    match json::from_str(&source) {
+        Ok(_) => {
+            println!("{}: ok", path);
+        }
+        Err(err) => {
+            eprint!("{}", err.render(&source));
+            process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use json_parser::json;
+use json_parser::json::BorrowedValue;
+use json_parser::json::Value;
+use json_parser::json::de::De;
+use json_parser::json::de::DuplicateKeys;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+
+fn borrowed(json: &str) -> BorrowedValue<'_> {
+    json::from_str_borrowed(json).unwrap_or_else(|err| panic!("{:?}: {}", json, err))
+}
+
+fn is_borrowed(value: &BorrowedValue) -> bool {
+    matches!(*value, BorrowedValue::String(Cow::Borrowed(_)))
+}
+
+#[test]
+fn strings_borrow_unless_escaped() {
+    let json = r#"{"plain": "text", "esc\u0061ped": "line\nbreak", "unicode": "caf\u00e9", "raw": "café"}"#;
+    let doc = borrowed(json);
+    let members = doc.as_object().unwrap();
+    let keys: Vec<bool> = members.iter().map(|(key, _)| matches!(key, Cow::Borrowed(_))).collect();
+    assert_eq!(keys, [true, false, true, true]);
+    let values: Vec<bool> = members.iter().map(|(_, value)| is_borrowed(value)).collect();
+    assert_eq!(values, [true, false, false, true]);
+
+    assert_eq!(doc.get("escaped").and_then(BorrowedValue::as_str), Some("line\nbreak"));
+    assert_eq!(doc.get("unicode").and_then(BorrowedValue::as_str), Some("caf\u{e9}"));
+    // Borrowed text points into the input itself.
+    let text = doc.get("plain").and_then(BorrowedValue::as_str).unwrap();
+    assert!(json.as_bytes().as_ptr_range().contains(&text.as_ptr()));
+}
+
+#[test]
+fn lookups() {
+    let doc = borrowed(r#"{"a": [1, {"b": null}], "c": true, "d": "x"}"#);
+    assert_eq!(doc.pointer("/a/1/b"), Some(&BorrowedValue::Null));
+    assert_eq!(doc.pointer("/a/0").and_then(BorrowedValue::as_number).and_then(|n| n.as_u64()), Some(1));
+    assert_eq!(doc.pointer("/a/2"), None);
+    assert_eq!(doc.pointer("/a/01"), None);
+    assert_eq!(doc.pointer("a"), None);
+    assert_eq!(doc.pointer(""), Some(&doc));
+    assert_eq!(doc.get("c").and_then(BorrowedValue::as_boolean), Some(true));
+    assert_eq!(doc.get("missing"), None);
+    assert_eq!(doc.get_index(0), None);
+    assert!(doc.get("a").unwrap().get_index(1).unwrap().is_object());
+    assert!(doc.get("d").unwrap().is_string() && doc.get("c").unwrap().is_boolean());
+}
+
+#[test]
+fn into_owned_matches_value() {
+    let json = r#"{"a": [1, -2.5, "x\ty", {"b": false}], "c": null, "d": {}}"#;
+    let owned: Value = borrowed(json).into_owned();
+    assert_eq!(owned, json::from_str(json).unwrap());
+    assert_eq!(Value::from(borrowed("[]")), Value::Array(Vec::new()));
+}
+
+#[test]
+fn members_stay_in_input_order() {
+    let a = borrowed(r#"{"a": 1, "b": 2}"#);
+    let b = borrowed(r#"{"b": 2, "a": 1}"#);
+    assert_ne!(a, b);
+    assert_eq!(a.into_owned(), b.into_owned());
+}
+
+#[test]
+fn duplicate_keys() {
+    let json = r#"{"a": 1, "b": 2, "a": 3}"#;
+    let doc = borrowed(json);
+    assert_eq!(doc.as_object().unwrap().len(), 2);
+    assert_eq!(doc.get("a").and_then(BorrowedValue::as_number).and_then(|n| n.as_u64()), Some(3));
+
+    let options = ParserOptions::new().duplicate_keys(DuplicateKeys::FirstWins);
+    let doc = De::with_options(json, options).borrowed_deserialization().unwrap();
+    assert_eq!(doc.get("a").and_then(BorrowedValue::as_number).and_then(|n| n.as_u64()), Some(1));
+
+    let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+    let err = De::with_options(json, options).borrowed_deserialization().unwrap_err();
+    assert_eq!((err.kind(), err.offset()), (ErrorKind::DuplicateKey, 17));
+}
+
+#[test]
+fn errors_match_value_parsing() {
+    for json in ["[1, ]", r#"{"a" 1}"#, "\"\\x\"", "[] []", ""] {
+        let err = json::from_str_borrowed(json).unwrap_err();
+        assert_eq!(err, json::from_str(json).unwrap_err(), "{:?}", json);
+    }
+}
+
+#[test]
+fn readers_produce_owned_strings() {
+    let doc = De::from_reader(&br#"{"a": "b"}"#[..]).borrowed_deserialization().unwrap();
+    let members = doc.as_object().unwrap();
+    assert!(matches!(members[0].0, Cow::Owned(_)));
+    assert!(!is_borrowed(&members[0].1));
+    assert_eq!(doc.get("a").and_then(BorrowedValue::as_str), Some("b"));
+}
@@ -0,0 +1,118 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::value::Number;
+use json_parser::json::value::NumberError;
+
+fn number(json: &str) -> Number {
+    match json::from_str(json) {
+        Ok(Value::Number(ref n)) => n.clone(),
+        other => panic!("{:?} parsed as {:?}", json, other),
+    }
+}
+
+#[test]
+fn accessors() {
+    let cases = [
+        ("0", Some(0), Some(0), Some(0.0)),
+        ("9223372036854775807", Some(i64::MAX as u64), Some(i64::MAX), Some(9.223372036854776e18)),
+        ("9223372036854775808", Some(1 << 63), None, Some(9.223372036854776e18)),
+        ("-1", None, Some(-1), Some(-1.0)),
+        ("1.5", None, None, Some(1.5)),
+        ("2.0", None, None, Some(2.0)),
+    ];
+    for (json, u, i, f) in cases {
+        let n = number(json);
+        assert_eq!((n.as_u64(), n.as_i64(), n.as_f64()), (u, i, f), "{}", json);
+        assert_eq!((n.is_u64(), n.is_i64(), n.is_f64()), (u.is_some(), i.is_some(), u.is_none() && i.is_none()), "{}", json);
+    }
+}
+
+#[test]
+fn checked_conversions() {
+    assert_eq!(number("255").to_u8(), Ok(255));
+    assert_eq!(number("256").to_u8(), Err(NumberError::OutOfRange("u8")));
+    assert_eq!(number("-128").to_i8(), Ok(-128));
+    assert_eq!(number("-129").to_i8(), Err(NumberError::OutOfRange("i8")));
+    assert_eq!(number("-1").to_u64(), Err(NumberError::OutOfRange("u64")));
+    assert_eq!(number("-1").to_isize(), Ok(-1));
+    assert_eq!(number("18446744073709551615").to_i128(), Ok(i128::from(u64::MAX)));
+    assert_eq!(number("65535").to_u16(), Ok(u16::MAX));
+    assert_eq!(number("-2147483648").to_i32(), Ok(i32::MIN));
+    assert_eq!(number("4294967296").to_u32(), Err(NumberError::OutOfRange("u32")));
+    assert_eq!(number("7").to_usize(), Ok(7));
+    assert_eq!(number("-32769").to_i16(), Err(NumberError::OutOfRange("i16")));
+    assert_eq!(number("-9223372036854775808").to_i64(), Ok(i64::MIN));
+}
+
+#[test]
+fn checked_conversions_from_floats() {
+    // Integral floats convert like the integer they hold.
+    assert_eq!(number("3.0").to_u8(), Ok(3));
+    assert_eq!(number("-2e1").to_i32(), Ok(-20));
+    assert_eq!(number("1e20").to_u128(), Ok(100_000_000_000_000_000_000));
+    assert_eq!(number("1e20").to_u64(), Err(NumberError::OutOfRange("u64")));
+    assert_eq!(number("2.5").to_i64(), Err(NumberError::Fractional("i64")));
+    assert_eq!(number("-0.5").to_u8(), Err(NumberError::Fractional("u8")));
+    assert_eq!(number("1e300").to_i128(), Err(NumberError::OutOfRange("i128")));
+    assert_eq!(Number::from(f64::NAN).to_u8(), Err(NumberError::OutOfRange("u8")));
+    assert_eq!(Number::from(f64::INFINITY).to_i64(), Err(NumberError::OutOfRange("i64")));
+}
+
+#[test]
+fn error_messages() {
+    assert_eq!(NumberError::OutOfRange("u8").to_string(), "number out of range for u8");
+    assert_eq!(
+        NumberError::Fractional("i32").to_string(),
+        "number with a fractional part cannot be converted to i32",
+    );
+    let err: Box<dyn std::error::Error> = Box::new(NumberError::OutOfRange("u8"));
+    assert_eq!(err.to_string(), "number out of range for u8");
+}
+
+#[test]
+fn from_primitives() {
+    assert_eq!(Number::from(7u8), number("7"));
+    assert_eq!(Number::from(-7i16), number("-7"));
+    assert_eq!(Number::from(0i32).as_u64(), Some(0));
+    assert_eq!(Number::from(usize::MAX).to_usize(), Ok(usize::MAX));
+    assert_eq!(Number::from(isize::MIN).to_isize(), Ok(isize::MIN));
+    assert_eq!(Number::from(1.5f32), number("1.5"));
+    assert_eq!(Number::from(u64::MAX), number("18446744073709551615"));
+    // 128-bit values in the range of the 64-bit variants stay integers.
+    assert_eq!(Number::from(u128::from(u64::MAX)).as_u64(), Some(u64::MAX));
+    assert_eq!(Number::from(i128::from(i64::MIN)).as_i64(), Some(i64::MIN));
+    assert!(Number::from(u128::MAX).as_u64().is_none());
+}
+
+#[test]
+fn integer_arithmetic() {
+    let add = |a: i64, b: i64| number(&a.to_string()).checked_add(&number(&b.to_string()));
+    assert_eq!(add(2, 3), Some(Number::from(5)));
+    assert_eq!(add(-2, 3), Some(Number::from(1)));
+    assert_eq!(number("3").checked_sub(&number("5")), Some(Number::from(-2)));
+    assert_eq!(number("-4").checked_mul(&number("-5")), Some(Number::from(20)));
+    // Results between `i64::MAX` and `u64::MAX` still fit.
+    let sum = number("9223372036854775807").checked_add(&number("1")).unwrap();
+    assert_eq!(sum.as_u64(), Some(1 << 63));
+    assert!(!sum.is_f64());
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+#[test]
+fn integer_arithmetic_overflow() {
+    assert_eq!(number("18446744073709551615").checked_add(&number("1")), None);
+    assert_eq!(number("-9223372036854775808").checked_sub(&number("1")), None);
+    assert_eq!(number("4294967296").checked_mul(&number("4294967296")), None);
+    assert_eq!(number("18446744073709551615").checked_sub(&number("1")), Some(Number::from(u64::MAX - 1)));
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+#[test]
+fn float_arithmetic() {
+    // With a float involved, floats round as they do.
+    assert_eq!(number("0.1").checked_add(&number("0.2")), Some(Number::from(0.1 + 0.2)));
+    assert_eq!(number("1").checked_mul(&number("0.5")), Some(Number::from(0.5)));
+    assert!(number("2").checked_sub(&number("1.0")).unwrap().is_f64());
+    assert_eq!(number("1e308").checked_mul(&number("10")), None);
+    assert_eq!(Number::from(f64::NAN).checked_add(&number("1")), None);
+}
@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::convert::FromJson;
+use json_parser::json::convert::from_value;
+use json_parser::json::de::De;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+// `json`, an object, with every member kept as raw text.
+fn raw(json: &str) -> Value {
+    let mut de = De::new(json);
+    for key in value(json).as_object().unwrap().keys() {
+        de = de.raw_pointer(&format!("/{}", key)).unwrap();
+    }
+    let doc = de.deserialization().unwrap();
+    assert!(doc.as_object().unwrap().values().all(|member| matches!(member, Value::Raw(_))), "{:?}", doc);
+    doc
+}
+
+fn read_error<T: FromJson + std::fmt::Debug>(json: &str) -> String {
+    match from_value::<T>(&value(json)) {
+        Ok(value) => panic!("{} read as {:?}", json, value),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn raw_values_are_read_through() {
+    let doc = raw(r#"{"a": [1, 2], "b": "x", "c": true, "d": 1.5, "e": null, "f": {"g": [3]}}"#);
+    assert_eq!(from_value::<Vec<u8>>(&doc["a"]).unwrap(), [1, 2]);
+    assert_eq!(from_value::<(u8, u64)>(&doc["a"]).unwrap(), (1, 2));
+    assert_eq!(from_value::<String>(&doc["b"]).unwrap(), "x");
+    assert_eq!(from_value::<char>(&doc["b"]).unwrap(), 'x');
+    assert!(from_value::<bool>(&doc["c"]).unwrap());
+    assert_eq!(from_value::<f64>(&doc["d"]).unwrap(), 1.5);
+    assert_eq!(from_value::<f32>(&doc["d"]).unwrap(), 1.5);
+    assert_eq!(from_value::<Option<String>>(&doc["e"]).unwrap(), None);
+    assert_eq!(from_value::<()>(&doc["e"]).unwrap(), ());
+    let nested: BTreeMap<String, BTreeMap<String, Vec<i32>>> = from_value(&raw(r#"{"f": {"g": [3]}}"#)).unwrap();
+    assert_eq!(nested["f"]["g"], [3]);
+
+    // `Value` itself keeps the raw text.
+    assert!(matches!(from_value::<Value>(&doc["f"]).unwrap(), Value::Raw(_)));
+    // Mismatches are reported against the value inside.
+    let err = from_value::<Vec<String>>(&doc["f"]).unwrap_err().to_string();
+    assert!(err.contains("expected array, found object"), "{}", err);
+}
+
+#[test]
+fn floats_in_range() {
+    assert_eq!(from_value::<f32>(&value("3.4028234663852886e38")).unwrap(), f32::MAX);
+    assert_eq!(from_value::<f32>(&value("-3.4028234663852886e38")).unwrap(), f32::MIN);
+    // Too small to represent rounds to zero, as parsing does.
+    assert_eq!(from_value::<f32>(&value("1e-50")).unwrap(), 0.0);
+    assert_eq!(from_value::<f64>(&value("1e300")).unwrap(), 1e300);
+
+    let err = read_error::<f32>("1e39");
+    assert!(err.contains("out of range for f32"), "{}", err);
+    let err = read_error::<f32>("-3.5e38");
+    assert!(err.contains("out of range for f32"), "{}", err);
+    let err = read_error::<f32>("\"1\"");
+    assert!(err.contains("expected f32, found string"), "{}", err);
+}
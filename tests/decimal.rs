@@ -0,0 +1,144 @@
+#![cfg(feature = "arbitrary_precision")]
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::value::Number;
+use json_parser::json::value::decimal::MAX_DIGITS;
+
+fn number(json: &str) -> Number {
+    match json::from_str(json) {
+        Ok(Value::Number(ref n)) => n.clone(),
+        other => panic!("{:?} parsed as {:?}", json, other),
+    }
+}
+
+fn hash(n: &Number) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    n.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn add(a: &str, b: &str) -> Option<String> {
+    number(a).checked_add(&number(b)).map(|n| n.as_str().to_owned())
+}
+
+fn sub(a: &str, b: &str) -> Option<String> {
+    number(a).checked_sub(&number(b)).map(|n| n.as_str().to_owned())
+}
+
+fn mul(a: &str, b: &str) -> Option<String> {
+    number(a).checked_mul(&number(b)).map(|n| n.as_str().to_owned())
+}
+
+#[test]
+fn big_integers() {
+    let n = number("340282366920938463463374607431768211455");
+    assert_eq!(n.to_u128(), Ok(u128::MAX));
+    assert_eq!(n.as_u64(), None);
+    assert_eq!(number("-170141183460469231731687303715884105728").to_i128(), Ok(i128::MIN));
+    assert!(number("340282366920938463463374607431768211456").to_u128().is_err());
+    assert_eq!(Value::from(u128::MAX).to_string(), "340282366920938463463374607431768211455");
+}
+
+#[test]
+fn arithmetic_is_exact() {
+    assert_eq!(add("18446744073709551615", "1").as_deref(), Some("18446744073709551616"));
+    assert_eq!(sub("-9223372036854775808", "1").as_deref(), Some("-9223372036854775809"));
+    assert_eq!(
+        mul("123456789012345678901234567890", "987654321098765432109876543210").as_deref(),
+        Some("121932631137021795226185032733622923332237463801111263526900"),
+    );
+    assert_eq!(add("0.1", "0.2").as_deref(), Some("0.3"));
+    assert_eq!(sub("1", "0.001").as_deref(), Some("0.999"));
+    assert_eq!(mul("-1.5", "0.02").as_deref(), Some("-0.03"));
+    assert_eq!(sub("2.5", "2.5").as_deref(), Some("0"));
+    assert_eq!(mul("1e300", "1e300"), None);
+}
+
+#[test]
+fn arithmetic_digit_bound() {
+    let under = format!("1e-{}", MAX_DIGITS - 2);
+    let over = format!("1e-{}", MAX_DIGITS - 1);
+    let sum = add("1", &under).unwrap();
+    assert_eq!(sum.len(), MAX_DIGITS as usize);
+    assert!(sum.starts_with("1.0") && sum.ends_with("01"), "{}", sum);
+    assert_eq!(add("1", &over), None);
+    assert_eq!(sub("1", &over), None);
+
+    let half = MAX_DIGITS as usize / 2;
+    let under = format!("0.{}", "9".repeat(half));
+    let over = format!("0.{}", "9".repeat(half + 1));
+    assert_eq!(mul(&under, &under).map(|n| n.len()), Some(MAX_DIGITS as usize + 2));
+    assert_eq!(mul(&under, &over), None);
+}
+
+#[test]
+fn equal_by_value() {
+    let cases = [
+        ("1.0", "1"),
+        ("1e2", "100"),
+        ("-0", "0"),
+        ("0.50", "5E-1"),
+        ("18446744073709551616", "1.8446744073709551616e19"),
+    ];
+    for (a, b) in cases {
+        assert_eq!(number(a), number(b), "{} == {}", a, b);
+        assert_eq!(hash(&number(a)), hash(&number(b)), "{} and {} hash alike", a, b);
+    }
+    assert_eq!(number("0.1"), Number::from(0.1));
+    assert_eq!(number("7"), Number::from(7u8));
+    // Apart as text, though the same as floats.
+    assert_ne!(number("0.1"), number("0.10000000000000001"));
+    assert_eq!(number("0.1").as_f64(), number("0.10000000000000001").as_f64());
+}
+
+#[test]
+fn ordered_by_value() {
+    let sorted = [
+        Number::from(f64::NEG_INFINITY),
+        number("-18446744073709551617"),
+        number("-1.5"),
+        number("0"),
+        number("0.1"),
+        number("0.10000000000000001"),
+        number("1"),
+        number("18446744073709551616"),
+        number("18446744073709551617"),
+        Number::from(f64::INFINITY),
+        Number::from(f64::NAN),
+    ];
+    for pair in sorted.windows(2) {
+        assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+    }
+    assert_eq!(Number::from(f64::NAN), Number::from(f64::NAN));
+}
+
+#[test]
+fn text_round_trips() {
+    let json = "[0.10000000000000001, 123456789012345678901234567890, -1.50, 1E-400]";
+    let value: Value = json::from_str(json).unwrap();
+    let written = value.to_string();
+    assert_eq!(written, "[0.10000000000000001,123456789012345678901234567890,-1.50,1E-400]");
+    assert_eq!(json::from_str(&written).unwrap().to_string(), written);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips() {
+    use json_parser::json::serde as json_serde;
+
+    let json = r#"{"a":0.10000000000000001,"b":[123456789012345678901234567890,-0]}"#;
+    let value: Value = json_serde::from_str(json).unwrap();
+    assert_eq!(value.to_string(), json);
+    assert_eq!(json_serde::to_string(&value).unwrap(), json);
+
+    // A number's text only becomes a number where a `Value` asks for one.
+    let n: f64 = json_serde::from_str("0.10000000000000001").unwrap();
+    assert_eq!(n, 0.1);
+    let nan = Value::from(f64::NAN);
+    assert_eq!(json_serde::to_string(&nan).unwrap(), "null");
+}
@@ -0,0 +1,228 @@
+#![cfg(feature = "derive")]
+
+use std::collections::BTreeMap;
+
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::convert::FromJson;
+use json_parser::json::convert::ToJson;
+use json_parser::json::convert::from_value;
+use json_parser::json::convert::to_value;
+use json_parser::json::de::De;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn read<T: FromJson>(json: &str) -> T {
+    from_value(&value(json)).unwrap_or_else(|err| panic!("{}: {}", json, err))
+}
+
+fn read_error<T: FromJson + std::fmt::Debug>(json: &str) -> String {
+    match from_value::<T>(&value(json)) {
+        Ok(value) => panic!("{} read as {:?}", json, value),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Address {
+    street: String,
+    #[json(rename = "street_2")]
+    second_line: Option<String>,
+    #[json(rename = "postCode")]
+    post_code: String,
+}
+
+#[test]
+fn rename() {
+    let address: Address = read(r#"{"street": "1 High St", "street_2": "Flat 2", "postCode": "AB1 2CD"}"#);
+    assert_eq!(address, Address {
+        street: String::from("1 High St"),
+        second_line: Some(String::from("Flat 2")),
+        post_code: String::from("AB1 2CD"),
+    });
+    let written = to_value(&address);
+    assert_eq!(written, value(r#"{"street": "1 High St", "street_2": "Flat 2", "postCode": "AB1 2CD"}"#));
+    assert_eq!(read::<Address>(&written.to_string()), address);
+}
+
+#[test]
+fn optional_fields() {
+    let address: Address = read(r#"{"street": "1 High St", "postCode": "AB1 2CD"}"#);
+    assert_eq!(address.second_line, None);
+    let address: Address = read(r#"{"street": "1 High St", "street_2": null, "postCode": "AB1 2CD"}"#);
+    assert_eq!(address.second_line, None);
+}
+
+#[test]
+fn missing_and_mistyped_fields() {
+    let err = read_error::<Address>(r#"{"street": "1 High St"}"#);
+    assert!(err.contains("missing field `postCode`"), "{}", err);
+    let err = read_error::<Address>(r#"{"street": 1, "postCode": "AB1 2CD"}"#);
+    assert!(err.contains("field `street`"), "{}", err);
+    assert!(err.contains("expected string, found number"), "{}", err);
+    let err = read_error::<Address>("[]");
+    assert!(err.contains("expected object"), "{}", err);
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Settings {
+    name: String,
+    #[json(default)]
+    verbose: bool,
+    #[json(default = "default_retries")]
+    retries: u32,
+    #[json(skip)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn default() {
+    let settings: Settings = read(r#"{"name": "a"}"#);
+    assert_eq!(settings, Settings {
+        name: String::from("a"),
+        verbose: false,
+        retries: 3,
+        cache: Vec::new(),
+    });
+    let settings: Settings = read(r#"{"name": "a", "verbose": true, "retries": 0}"#);
+    assert!(settings.verbose);
+    assert_eq!(settings.retries, 0);
+}
+
+#[test]
+fn skip() {
+    let settings = Settings {
+        name: String::from("a"),
+        verbose: true,
+        retries: 1,
+        cache: vec![1, 2, 3],
+    };
+    assert_eq!(to_value(&settings), value(r#"{"name": "a", "verbose": true, "retries": 1}"#));
+    let settings: Settings = read(r#"{"name": "a", "cache": [1, 2, 3]}"#);
+    assert!(settings.cache.is_empty());
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Page {
+    id: u64,
+    title: String,
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Tagged {
+    #[json(flatten)]
+    page: Page,
+    #[json(flatten)]
+    extra: BTreeMap<String, Value>,
+}
+
+#[test]
+fn flatten() {
+    let tagged: Tagged = read(r#"{"id": 7, "title": "Home", "lang": "en"}"#);
+    assert_eq!(tagged.page, Page {
+        id: 7,
+        title: String::from("Home"),
+    });
+    // Every member not named by a field of `Tagged` itself, so `extra`
+    // also sees those read by `page`.
+    assert_eq!(tagged.extra.get("lang"), Some(&Value::from("en")));
+    assert_eq!(to_value(&tagged), value(r#"{"id": 7, "title": "Home", "lang": "en"}"#));
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Meters(f64);
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Point(i32, i32);
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Marker;
+
+#[test]
+fn tuple_and_unit_structs() {
+    assert_eq!(to_value(&Meters(1.5)), value("1.5"));
+    assert_eq!(read::<Meters>("1.5"), Meters(1.5));
+    assert_eq!(to_value(&Point(1, -2)), value("[1, -2]"));
+    assert_eq!(read::<Point>("[1, -2]"), Point(1, -2));
+    assert!(read_error::<Point>("[1]").contains("array of length 2"));
+    assert_eq!(to_value(&Marker), value("null"));
+    assert_eq!(read::<Marker>("null"), Marker);
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+enum Shape {
+    Empty,
+    #[json(rename = "dot")]
+    Point,
+    Circle(f64),
+    Line(Point, Point),
+    Rect { width: u32, height: u32 },
+}
+
+#[test]
+fn enums() {
+    let cases = [
+        (Shape::Empty, r#""Empty""#),
+        (Shape::Point, r#""dot""#),
+        (Shape::Circle(0.5), r#"{"Circle": 0.5}"#),
+        (Shape::Line(Point(0, 0), Point(1, 1)), r#"{"Line": [[0, 0], [1, 1]]}"#),
+        (Shape::Rect { width: 2, height: 3 }, r#"{"Rect": {"width": 2, "height": 3}}"#),
+    ];
+    for (shape, json) in cases {
+        assert_eq!(to_value(&shape), value(json), "{:?}", shape);
+        assert_eq!(read::<Shape>(json), shape, "{}", json);
+    }
+    assert!(read_error::<Shape>(r#""Point""#).contains("unknown variant `Point`"));
+    assert!(read_error::<Shape>(r#"{"Square": 1}"#).contains("unknown variant `Square`"));
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct Nested {
+    shapes: Vec<Shape>,
+    by_name: BTreeMap<String, Option<Box<Page>>>,
+}
+
+#[test]
+fn nested() {
+    let json = r#"{
+        "shapes": ["Empty", {"Circle": 2}],
+        "by_name": {"home": {"id": 1, "title": "Home"}, "gone": null}
+    }"#;
+    let nested: Nested = read(json);
+    assert_eq!(nested.shapes, [Shape::Empty, Shape::Circle(2.0)]);
+    assert_eq!(nested.by_name["gone"], None);
+    assert_eq!(to_value(&nested), value(json));
+
+    let err = read_error::<Nested>(r#"{"shapes": ["Empty", {"Circle": "x"}], "by_name": {}}"#);
+    assert!(err.contains("field `shapes`: index 1"), "{}", err);
+}
+
+#[test]
+fn usable_through_the_traits() {
+    let page = Page {
+        id: 1,
+        title: String::from("Home"),
+    };
+    assert_eq!(Page::from_json(&page.to_json()).unwrap(), page);
+}
+
+#[test]
+fn raw_values() {
+    let json = r#"{"page": {"id": 1, "title": "Home"}, "shape": {"Rect": {"width": 2, "height": 3}}, "point": [1, 2], "marker": null}"#;
+    let doc = ["/page", "/shape", "/point", "/marker"]
+        .iter()
+        .fold(De::new(json), |de, pointer| de.raw_pointer(pointer).unwrap())
+        .deserialization()
+        .unwrap();
+    assert!(matches!(doc["page"], Value::Raw(_)));
+    assert_eq!(from_value::<Page>(&doc["page"]).unwrap().title, "Home");
+    assert_eq!(from_value::<Shape>(&doc["shape"]).unwrap(), Shape::Rect { width: 2, height: 3 });
+    assert_eq!(from_value::<Point>(&doc["point"]).unwrap(), Point(1, 2));
+    assert_eq!(from_value::<Marker>(&doc["marker"]).unwrap(), Marker);
+}
@@ -0,0 +1,89 @@
+use std::error;
+use std::io;
+
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::de::ErrorKind;
+
+fn error(json: &str) -> Error {
+    match json::from_str(json) {
+        Ok(value) => panic!("{:?} parsed as {:?}", json, value),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn kind_expected_and_found() {
+    let err = error(r#"{"a" 1}"#);
+    assert_eq!(err.kind(), ErrorKind::UnexpectedCharacter);
+    assert_eq!(err.expected(), Some("':'"));
+    assert_eq!(err.found(), Some('1'));
+    assert_eq!(err.detail(), None);
+
+    let err = error("[1, 2");
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(err.expected(), Some("',' or ']'"));
+    assert_eq!(err.found(), None);
+
+    let err = error("1 2");
+    assert_eq!((err.kind(), err.found()), (ErrorKind::TrailingCharacters, Some('2')));
+}
+
+#[test]
+fn display() {
+    let cases = [
+        (r#"{"a" 1}"#, "unexpected character, expected ':', found '1' at line 1 column 6"),
+        ("[1, 2", "unexpected end of input, expected ',' or ']' at line 1 column 6"),
+        (r#""\x""#, "invalid escape, expected escape character, found 'x' at line 1 column 3"),
+        ("[1,\n  tru]", "unexpected character, expected `true`, found ']' at line 2 column 6"),
+    ];
+    for (json, message) in cases {
+        assert_eq!(error(json).to_string(), message, "{:?}", json);
+    }
+}
+
+#[test]
+fn custom_errors_have_no_position() {
+    let err = Error::custom("bad");
+    assert_eq!(err.kind(), ErrorKind::Custom);
+    assert_eq!(err.detail(), Some("bad"));
+    assert!(!err.is_located());
+    assert_eq!(err.to_string(), "invalid data: bad");
+
+    let err = error("[1, x]");
+    let position = err.position();
+    let located = Error::custom("bad").locate(position);
+    assert!(located.is_located());
+    assert_eq!(located.to_string(), "invalid data: bad at line 1 column 5");
+    // An error that already has a position keeps it.
+    assert_eq!(err.locate(Default::default()).position(), position);
+}
+
+#[test]
+fn invalid_utf8() {
+    let err = json::from_slice(b"[\"\xff\"]").unwrap_err();
+    assert_eq!((err.kind(), err.offset()), (ErrorKind::InvalidUtf8, 2));
+    let err = json::from_reader(&b"[\xff]"[..]).unwrap_err();
+    assert_eq!((err.kind(), err.offset()), (ErrorKind::InvalidUtf8, 1));
+}
+
+struct Failing;
+
+impl io::Read for Failing {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"))
+    }
+}
+
+#[test]
+fn io_errors() {
+    let err = json::from_reader(Failing).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io(io::ErrorKind::ConnectionReset));
+    assert!(err.to_string().starts_with("io error: "), "{}", err);
+}
+
+#[test]
+fn is_a_std_error() {
+    let err: Box<dyn error::Error> = Box::new(error("nul"));
+    assert_eq!(err.to_string(), "unexpected end of input, expected `null` at line 1 column 4");
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+use std::io::Read as _;
+
+use json_parser::json::de::Error;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::Event;
+use json_parser::json::de::Parser;
+use json_parser::json::de::PathSegment;
+use json_parser::json::de::StrRead;
+use json_parser::json::value::Number;
+
+fn events(json: &str) -> Result<Vec<Event<'_>>, Error> {
+    Parser::new(StrRead::new(json)).collect()
+}
+
+fn number(n: u64) -> Event<'static> {
+    Event::Number(Number::from(n))
+}
+
+#[test]
+fn event_sequence() {
+    let json = r#"{"a": [1, "two", {}], "b": {"c": null}, "d": true, "e": []}"#;
+    assert_eq!(events(json).unwrap(), [
+        Event::StartObject,
+        Event::Key(Cow::Borrowed("a")),
+        Event::StartArray,
+        number(1),
+        Event::String(Cow::Borrowed("two")),
+        Event::StartObject,
+        Event::EndObject,
+        Event::EndArray,
+        Event::Key(Cow::Borrowed("b")),
+        Event::StartObject,
+        Event::Key(Cow::Borrowed("c")),
+        Event::Null,
+        Event::EndObject,
+        Event::Key(Cow::Borrowed("d")),
+        Event::Bool(true),
+        Event::Key(Cow::Borrowed("e")),
+        Event::StartArray,
+        Event::EndArray,
+        Event::EndObject,
+    ]);
+}
+
+#[test]
+fn scalar_documents() {
+    assert_eq!(events(" 42 ").unwrap(), [number(42)]);
+    assert_eq!(events("false").unwrap(), [Event::Bool(false)]);
+    assert_eq!(events(r#""x""#).unwrap(), [Event::String(Cow::Borrowed("x"))]);
+}
+
+#[test]
+fn strings_borrow_unless_escaped() {
+    let list = events(r#"["plain", "esc\naped"]"#).unwrap();
+    assert!(matches!(list[1], Event::String(Cow::Borrowed("plain"))));
+    assert!(matches!(list[2], Event::String(Cow::Owned(ref str)) if str == "esc\naped"));
+
+    // Readers cannot lend out their input.
+    let list: Vec<Event> = Parser::from_reader(&b"[\"plain\"]"[..]).collect::<Result<_, _>>().unwrap();
+    assert!(matches!(list[1], Event::String(Cow::Owned(ref str)) if str == "plain"));
+}
+
+#[test]
+fn errors_end_the_events() {
+    let mut parser = Parser::new(StrRead::new("[1, }"));
+    assert_eq!(parser.next_event().unwrap(), Some(Event::StartArray));
+    assert_eq!(parser.next_event().unwrap(), Some(number(1)));
+    let err = parser.next_event().unwrap_err();
+    assert_eq!((err.kind(), err.offset()), (ErrorKind::UnexpectedCharacter, 4));
+    assert_eq!(parser.next_event().unwrap(), None);
+}
+
+#[test]
+fn trailing_characters() {
+    let mut parser = Parser::new(StrRead::new("1 2"));
+    assert_eq!(parser.next_event().unwrap(), Some(number(1)));
+    assert_eq!(parser.next_event().unwrap_err().kind(), ErrorKind::TrailingCharacters);
+}
+
+#[test]
+fn positions() {
+    let mut parser = Parser::new(StrRead::new("{\n  \"a\": [true]\n}"));
+    let mut starts = Vec::new();
+    while parser.next_event().unwrap().is_some() {
+        let position = parser.event_position();
+        starts.push((position.line, position.column));
+    }
+    assert_eq!(starts, [(1, 1), (2, 3), (2, 8), (2, 9), (2, 13), (3, 1)]);
+}
+
+#[test]
+fn path_and_pointer() {
+    let json = r#"{"a": [10, {"b/c": 20, "~d": [30]}], "e": 40}"#;
+    let mut parser = Parser::new(StrRead::new(json));
+    let mut seen = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        if let Event::Number(n) = event {
+            seen.push((n.as_u64().unwrap(), parser.path(), parser.pointer(), parser.depth()));
+        }
+    }
+    let key = |key: &str| PathSegment::Key(String::from(key));
+    assert_eq!(seen, [
+        (10, vec![key("a"), PathSegment::Index(0)], String::from("/a/0"), 2),
+        (20, vec![key("a"), PathSegment::Index(1), key("b/c")], String::from("/a/1/b~1c"), 3),
+        (30, vec![key("a"), PathSegment::Index(1), key("~d"), PathSegment::Index(0)], String::from("/a/1/~0d/0"), 4),
+        (40, vec![key("e")], String::from("/e"), 1),
+    ]);
+}
+
+#[test]
+fn pointer_of_the_root() {
+    let mut parser = Parser::new(StrRead::new("[]"));
+    assert_eq!(parser.pointer(), "");
+    parser.next_event().unwrap();
+    assert_eq!(parser.path(), []);
+    assert_eq!(parser.pointer(), "");
+}
+
+// Reads the first value's events, then hands back the reader.
+fn first_value(input: &[u8]) -> (usize, Vec<u8>) {
+    let mut parser = Parser::from_reader(input).multiple_values(true);
+    let mut count = 0;
+    loop {
+        parser.next_event().unwrap();
+        count += 1;
+        if parser.depth() == 0 {
+            break
+        }
+    }
+    let (mut reader, mut rest) = parser.into_inner();
+    reader.read_to_end(&mut rest).unwrap();
+    (count, rest)
+}
+
+#[test]
+fn into_inner_returns_unparsed_input() {
+    assert_eq!(first_value(b"{\"a\": [1]} [2]\n"), (6, b" [2]\n".to_vec()));
+    assert_eq!(first_value(b"[]{}"), (2, b"{}".to_vec()));
+    // A number only ends at the character after it, which was read ahead.
+    assert_eq!(first_value(b"12,34"), (1, b",34".to_vec()));
+    assert_eq!(first_value("1\"\u{e9}\"".as_bytes()), (1, "\"\u{e9}\"".as_bytes().to_vec()));
+    assert_eq!(first_value("[]\u{e9}".as_bytes()), (2, "\u{e9}".as_bytes().to_vec()));
+    assert_eq!(first_value(b"true"), (1, Vec::new()));
+
+    // Past the reader's buffer.
+    let mut input = b"[0]".to_vec();
+    input.extend_from_slice(&[b' '; 20_000]);
+    input.extend_from_slice(b"[1]");
+    let (_, rest) = first_value(&input);
+    assert_eq!(rest.len(), 20_003);
+    assert!(rest.ends_with(b" [1]"));
+}
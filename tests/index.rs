@@ -0,0 +1,90 @@
+use std::panic;
+
+use json_parser::json;
+use json_parser::json::Value;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+// The message `f` panics with.
+fn panic_message<F: FnOnce() + panic::UnwindSafe>(f: F) -> String {
+    let payload = panic::catch_unwind(f).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => String::from(*payload.downcast::<&str>().unwrap()),
+    }
+}
+
+#[test]
+fn index_misses_are_null() {
+    let doc = value(r#"{"a": [1, {"b": 2}], "c": "x"}"#);
+    assert_eq!(doc["a"][1]["b"], Value::from(2));
+    assert_eq!(doc["missing"], Value::Null);
+    assert_eq!(doc["a"][5], Value::Null);
+    assert_eq!(doc["c"]["d"], Value::Null);
+    assert_eq!(doc[0], Value::Null);
+    assert_eq!(doc["a"]["b"], Value::Null);
+    assert_eq!(doc[String::from("c")], Value::from("x"));
+    assert_eq!(doc[&String::from("c")], Value::from("x"));
+}
+
+#[test]
+fn get_and_get_mut() {
+    let mut doc = value(r#"{"a": [1, 2]}"#);
+    assert_eq!(doc.get("a").and_then(|a| a.get(1)), Some(&Value::from(2)));
+    assert_eq!(doc.get("b"), None);
+    assert_eq!(doc.get(0), None);
+    *doc.get_mut("a").unwrap().get_mut(0).unwrap() = Value::from("one");
+    assert!(doc.get_mut("b").is_none());
+    assert_eq!(doc, value(r#"{"a": ["one", 2]}"#));
+}
+
+#[test]
+fn index_mut_inserts_keys() {
+    let mut doc = value(r#"{"name": "Ann"}"#);
+    doc["address"]["city"] = Value::from("Leeds");
+    doc["address"]["lines"] = Value::Array(vec![Value::from("1 High St")]);
+    doc["address"]["lines"][0] = Value::from("2 High St");
+    assert_eq!(doc, value(r#"{"name": "Ann", "address": {"city": "Leeds", "lines": ["2 High St"]}}"#));
+
+    let mut doc = Value::Null;
+    doc["a"]["b"]["c"] = Value::from(true);
+    assert_eq!(doc, value(r#"{"a": {"b": {"c": true}}}"#));
+}
+
+#[test]
+fn index_mut_panics_where_it_cannot_insert() {
+    let message = panic_message(|| {
+        let mut doc = value("[1]");
+        doc[1] = Value::Null;
+    });
+    assert_eq!(message, "cannot access index 1 of JSON array of length 1");
+    let message = panic_message(|| {
+        let mut doc = value("[1]");
+        doc["a"] = Value::Null;
+    });
+    assert_eq!(message, "cannot access key \"a\" in JSON array");
+    let message = panic_message(|| {
+        let mut doc = Value::Null;
+        doc[0] = Value::Null;
+    });
+    assert_eq!(message, "cannot access index 0 of JSON null");
+}
+
+#[test]
+fn mutable_accessors() {
+    let mut doc = value(r#"[true, 1, "a", [], {}]"#);
+    *doc[0].as_boolean_mut().unwrap() = false;
+    *doc[1].as_number_mut().unwrap() = 2.into();
+    doc[2].as_str_mut().unwrap().push('b');
+    doc[3].as_array_mut().unwrap().push(Value::Null);
+    doc[4].as_object_mut().unwrap().insert(String::from("k"), Value::Null);
+    assert_eq!(doc, value(r#"[false, 2, "ab", [null], {"k": null}]"#));
+
+    assert!(doc[0].as_number_mut().is_none());
+    assert!(doc[1].as_str_mut().is_none());
+    assert!(doc[2].as_array_mut().is_none());
+    assert!(doc[3].as_object_mut().is_none());
+    assert!(doc[4].as_boolean_mut().is_none());
+}
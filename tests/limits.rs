@@ -0,0 +1,148 @@
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::Value;
+use json_parser::json::de::De;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+use json_parser::json::de::PushParser;
+use json_parser::json::de::Status;
+use json_parser::json::de::StreamDeserializer;
+
+fn push(json: &str, options: ParserOptions) -> Result<Value, Error> {
+    let mut parser = PushParser::with_options(options);
+    if let Status::Complete(value) = parser.feed(json.as_bytes())? {
+        return Ok(value)
+    }
+    Ok(parser.finish()?.expect("a value"))
+}
+
+// Parses `json` with both the pull and the push parser, which must agree.
+fn parse(json: &str, options: ParserOptions) -> Result<Value, ErrorKind> {
+    let pulled = De::with_options(json, options.clone()).deserialization();
+    let pushed = push(json, options);
+    match (pulled, pushed) {
+        (Ok(a), Ok(b)) => {
+            assert_eq!(a, b, "{}", json);
+            Ok(a)
+        }
+        (Err(a), Err(b)) => {
+            assert_eq!((a.kind(), a.position()), (b.kind(), b.position()), "{}", json);
+            Err(a.kind())
+        }
+        (a, b) => {
+            panic!("{}: pulled {:?}, pushed {:?}", json, a, b)
+        }
+    }
+}
+
+fn assert_limit(options: ParserOptions, under: &str, over: &str, kind: ErrorKind) {
+    assert!(parse(under, options.clone()).is_ok(), "{}", under);
+    assert_eq!(parse(over, options), Err(kind), "{}", over);
+}
+
+#[test]
+fn max_depth() {
+    let options = ParserOptions::new().max_depth(3);
+    assert_limit(options.clone(), "[[[]]]", "[[[[]]]]", ErrorKind::DepthExceeded);
+    assert_limit(options, r#"{"a": [{}]}"#, r#"{"a": [{"b": []}]}"#, ErrorKind::DepthExceeded);
+}
+
+#[test]
+fn max_depth_default() {
+    let under = "[".repeat(128) + &"]".repeat(128);
+    let over = "[".repeat(129) + &"]".repeat(129);
+    assert_limit(ParserOptions::new(), &under, &over, ErrorKind::DepthExceeded);
+}
+
+#[test]
+fn max_input_len() {
+    let options = ParserOptions::new().max_input_len(6);
+    assert_limit(options.clone(), "[1, 2]", "[1, 23]", ErrorKind::InputTooLong);
+    assert_limit(options, "\"\u{e9}\u{e9}\"", "\"\u{e9}\u{e9}a\"", ErrorKind::InputTooLong);
+}
+
+#[test]
+fn max_string_len() {
+    let options = ParserOptions::new().max_string_len(4);
+    assert_limit(options.clone(), r#""abcd""#, r#""abcde""#, ErrorKind::StringTooLong);
+    // Measured as written, so escapes and multi-byte characters count in
+    // full.
+    assert_limit(options.clone(), r#""\n\n""#, r#""\n\na""#, ErrorKind::StringTooLong);
+    assert_limit(options.clone(), "\"\u{e9}\u{e9}\"", "\"\u{e9}\u{e9}a\"", ErrorKind::StringTooLong);
+    assert_limit(options, r#"{"abcd": 1}"#, r#"{"abcde": 1}"#, ErrorKind::StringTooLong);
+}
+
+#[test]
+fn max_number_len() {
+    let options = ParserOptions::new().max_number_len(4);
+    assert_limit(options.clone(), "1234", "12345", ErrorKind::NumberTooLong);
+    assert_limit(options.clone(), "-1.5", "-1.55", ErrorKind::NumberTooLong);
+    assert_limit(options, "[1e10]", "[1e100]", ErrorKind::NumberTooLong);
+}
+
+#[test]
+fn max_members() {
+    let options = ParserOptions::new().max_members(2);
+    assert_limit(options.clone(), r#"{"a": 1, "b": 2}"#, r#"{"a": 1, "b": 2, "c": 3}"#, ErrorKind::TooManyMembers);
+    // Repeated keys count each time.
+    assert_limit(options, r#"{"a": 1, "a": 2}"#, r#"{"a": 1, "a": 2, "a": 3}"#, ErrorKind::TooManyMembers);
+}
+
+#[test]
+fn max_elements() {
+    let options = ParserOptions::new().max_elements(2);
+    assert_limit(options.clone(), "[1, 2]", "[1, 2, 3]", ErrorKind::TooManyElements);
+    assert_limit(options, "[[1, 2], [3, 4]]", "[[1, 2], [3, 4, 5]]", ErrorKind::TooManyElements);
+    let options = ParserOptions::new().max_elements(0);
+    assert_limit(options, "[]", "[1]", ErrorKind::TooManyElements);
+}
+
+#[test]
+fn limits_apply_to_readers() {
+    let options = ParserOptions::new().max_string_len(2);
+    let err = De::from_reader_with(&b"[\"abc\"]"[..], options.clone()).deserialization().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StringTooLong);
+    let mut stream = StreamDeserializer::from_reader_with(&b"[\"ab\"]\n[\"abc\"]"[..], options);
+    assert!(stream.next().unwrap().is_ok());
+    assert_eq!(stream.next().unwrap().unwrap_err().kind(), ErrorKind::StringTooLong);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn limits_apply_to_serde_readers() {
+    use json_parser::json::serde::Deserializer;
+    use serde::Deserialize;
+
+    let options = ParserOptions::new().max_elements(2);
+    let mut deserializer = Deserializer::from_reader_with(&b"[1, 2, 3]"[..], options);
+    let err = Vec::<u8>::deserialize(&mut deserializer).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyElements);
+}
+
+#[test]
+fn explicit_stack() {
+    let depth = 100_000;
+    let array = "[".repeat(depth) + &"]".repeat(depth);
+    let object = "{\"a\":".repeat(depth) + "null" + &"}".repeat(depth);
+    // The innermost array is empty, while the innermost object holds `null`.
+    for (json, levels) in [(&array, depth - 1), (&object, depth)] {
+        let options = ParserOptions::new().explicit_stack(true).max_depth(depth);
+        let value = De::with_options(json, options.clone()).deserialization().unwrap();
+        let mut level = &value;
+        let mut count = 0;
+        while let Some(inner) = level.get(0).or_else(|| level.get("a")) {
+            level = inner;
+            count += 1;
+        }
+        assert_eq!(count, levels);
+        value.drop_iteratively();
+
+        let value = De::with_options(json, options.clone()).borrowed_deserialization().unwrap();
+        value.drop_iteratively();
+
+        let options = options.max_depth(depth - 1);
+        let err = De::with_options(json, options).deserialization().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DepthExceeded);
+    }
+    assert_eq!(json::from_str(&array).unwrap_err().kind(), ErrorKind::DepthExceeded);
+}
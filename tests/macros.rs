@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+
+use json_parser::json;
+use json_parser::json::Map;
+use json_parser::json::Value;
+use json_parser::json::value::Number;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+#[test]
+fn literals() {
+    assert_eq!(json_parser::json!(null), Value::Null);
+    assert_eq!(json_parser::json!(true), Value::Boolean(true));
+    assert_eq!(json_parser::json!(false), Value::Boolean(false));
+    assert_eq!(json_parser::json!(1), value("1"));
+    assert_eq!(json_parser::json!(-1.5), value("-1.5"));
+    assert_eq!(json_parser::json!("a"), value(r#""a""#));
+    assert_eq!(json_parser::json!([]), Value::Array(Vec::new()));
+    assert_eq!(json_parser::json!({}), Value::Object(Map::new()));
+}
+
+#[test]
+fn nested() {
+    let built = json_parser::json!({
+        "a": [1, null, true, [false, {}], {"b": "c"}],
+        "d": {"e": {"f": []}},
+        "g": -0.5
+    });
+    assert_eq!(built, value(r#"{"a": [1, null, true, [false, {}], {"b": "c"}], "d": {"e": {"f": []}}, "g": -0.5}"#));
+}
+
+#[test]
+fn trailing_commas() {
+    assert_eq!(json_parser::json!([1, 2,]), value("[1, 2]"));
+    assert_eq!(json_parser::json!([null, [],]), value("[null, []]"));
+    assert_eq!(json_parser::json!({"a": 1,}), value(r#"{"a": 1}"#));
+    assert_eq!(json_parser::json!({"a": {},}), value(r#"{"a": {}}"#));
+}
+
+#[test]
+fn interpolated_values() {
+    let name = "Ann";
+    let phones = vec!["1", "2"];
+    let age: Option<u8> = None;
+    let built = json_parser::json!({
+        "name": name,
+        "phones": phones,
+        "age": age,
+        "sum": 1 + 2,
+        "list": [name, 2 * 3, String::from("x")],
+    });
+    assert_eq!(built, value(r#"{"name": "Ann", "phones": ["1", "2"], "age": null, "sum": 3, "list": ["Ann", 6, "x"]}"#));
+
+    // Anything with a `From` impl into `Value` can be spliced in.
+    let inner = json_parser::json!({"x": 1});
+    assert_eq!(json_parser::json!([inner.clone(), ()]), value(r#"[{"x": 1}, null]"#));
+    assert_eq!(json_parser::json!(Number::from(7u8)), value("7"));
+    assert_eq!(json_parser::json!(Cow::Borrowed("c")), value(r#""c""#));
+    assert_eq!(json_parser::json!(&[1u8, 2][..]), value("[1, 2]"));
+    assert_eq!(json_parser::json!(u64::MAX), value("18446744073709551615"));
+    assert_eq!(json_parser::json!(i64::MIN), value("-9223372036854775808"));
+}
+
+#[test]
+fn interpolated_keys() {
+    let key = "k";
+    let prefix = String::from("p");
+    let built = json_parser::json!({
+        key: 1,
+        (format!("{}-{}", prefix, 2)): 2,
+        (prefix.as_str()): 3,
+    });
+    assert_eq!(built, value(r#"{"k": 1, "p-2": 2, "p": 3}"#));
+}
+
+#[test]
+fn member_order_and_duplicates() {
+    let built = json_parser::json!({"b": 1, "a": 2, "b": 3});
+    assert_eq!(built, value(r#"{"b": 3, "a": 2}"#));
+    let keys: Vec<&str> = built.as_object().unwrap().keys().map(String::as_str).collect();
+    if cfg!(feature = "preserve_order") {
+        assert_eq!(keys, ["b", "a"]);
+    } else {
+        assert_eq!(keys, ["a", "b"]);
+    }
+}
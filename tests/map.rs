@@ -0,0 +1,134 @@
+use json_parser::json;
+use json_parser::json::Map;
+use json_parser::json::Value;
+use json_parser::json::value::map::Entry;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn keys(map: &Map) -> Vec<&str> {
+    map.keys().map(String::as_str).collect()
+}
+
+// The order members come out in, given the order they went in.
+fn ordered<'a>(keys: &[&'a str]) -> Vec<&'a str> {
+    let mut keys = keys.to_vec();
+    if cfg!(not(feature = "preserve_order")) {
+        keys.sort_unstable();
+    }
+    keys
+}
+
+fn map(keys: &[&str]) -> Map {
+    keys.iter().enumerate().map(|(index, key)| (key.to_string(), Value::from(index))).collect()
+}
+
+fn map_of(members: &[(&str, &str)]) -> Map {
+    members.iter().map(|&(key, value)| (String::from(key), Value::from(value))).collect()
+}
+
+#[test]
+fn iteration_order() {
+    let map = map(&["b", "c", "a"]);
+    assert_eq!(keys(&map), ordered(&["b", "c", "a"]));
+    let pairs: Vec<(String, Value)> = map.clone().into_iter().collect();
+    assert_eq!(pairs.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), ordered(&["b", "c", "a"]));
+    assert_eq!(map.values().count(), 3);
+    assert_eq!(map.get("b"), Some(&Value::from(0)));
+}
+
+#[test]
+fn parsed_and_written_order() {
+    let doc = value(r#"{"z": 1, "y": {"b": 2, "a": 3}, "x": 4}"#);
+    let written = doc.to_string();
+    if cfg!(feature = "preserve_order") {
+        assert_eq!(written, r#"{"z":1,"y":{"b":2,"a":3},"x":4}"#);
+    } else {
+        assert_eq!(written, r#"{"x":4,"y":{"a":3,"b":2},"z":1}"#);
+    }
+    // A repeated key keeps the first one's place and the last one's value.
+    let doc = value(r#"{"b": 1, "a": 2, "b": 3}"#);
+    assert_eq!(keys(doc.as_object().unwrap()), ordered(&["b", "a"]));
+    assert_eq!(doc["b"], Value::from(3));
+}
+
+#[test]
+fn insert_replaces_in_place() {
+    let mut map = map(&["b", "c", "a"]);
+    assert_eq!(map.insert(String::from("c"), Value::from("new")), Some(Value::from(1)));
+    assert_eq!(keys(&map), ordered(&["b", "c", "a"]));
+    assert_eq!(map.get("c"), Some(&Value::from("new")));
+    assert_eq!(map.insert(String::from("d"), Value::Null), None);
+    assert_eq!(keys(&map), ordered(&["b", "c", "a", "d"]));
+}
+
+#[test]
+fn remove_keeps_the_rest_in_order() {
+    let mut map = map(&["d", "c", "b", "a"]);
+    assert_eq!(map.remove("c"), Some(Value::from(1)));
+    assert_eq!(map.remove("c"), None);
+    assert_eq!(keys(&map), ordered(&["d", "b", "a"]));
+    // Lookups still find the members that moved up.
+    assert_eq!((map.get("b"), map.get("a")), (Some(&Value::from(2)), Some(&Value::from(3))));
+    map.insert(String::from("c"), Value::from(4));
+    assert_eq!(keys(&map), ordered(&["d", "b", "a", "c"]));
+    assert_eq!(map.len(), 4);
+    map.clear();
+    assert!(map.is_empty() && map.get("d").is_none());
+}
+
+#[test]
+fn entries() {
+    let mut map = map(&["b", "a"]);
+    *map.entry("a").or_insert(Value::Null) = Value::from("a");
+    map.entry("c").or_insert_with(|| Value::from("c"));
+    map.entry("b").and_modify(|value| *value = Value::from("b")).or_insert(Value::Null);
+    assert_eq!(map, map_of(&[("a", "a"), ("b", "b"), ("c", "c")]));
+    assert_eq!(keys(&map), ordered(&["b", "a", "c"]));
+
+    match map.entry("b") {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.key(), "b");
+            assert_eq!(entry.remove(), Value::from("b"));
+        }
+        Entry::Vacant(_) => {
+            panic!("`b` is present")
+        }
+    }
+    match map.entry("d") {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), "d");
+            entry.insert(Value::from("d"));
+        }
+        Entry::Occupied(_) => {
+            panic!("`d` is absent")
+        }
+    }
+    assert_eq!(keys(&map), ordered(&["a", "c", "d"]));
+}
+
+#[test]
+fn equality_ignores_order() {
+    assert_eq!(map_of(&[("a", "1"), ("b", "2")]), map_of(&[("b", "2"), ("a", "1")]));
+    assert_ne!(map_of(&[("a", "1"), ("b", "2")]), map_of(&[("a", "1"), ("b", "3")]));
+    assert_ne!(map_of(&[("a", "1")]), map_of(&[("a", "1"), ("b", "2")]));
+    assert_eq!(value(r#"{"a": 1, "b": 2}"#), value(r#"{"b": 2, "a": 1}"#));
+}
+
+#[test]
+fn mutation_through_iterators() {
+    let mut map = map(&["b", "a"]);
+    for (key, value) in map.iter_mut() {
+        *value = Value::from(key.as_str());
+    }
+    for value in map.values_mut() {
+        if let Value::String(ref mut str) = *value {
+            str.push('!');
+        }
+    }
+    assert_eq!(map, map_of(&[("a", "a!"), ("b", "b!")]));
+    map.extend(vec![(String::from("c"), Value::Null), (String::from("a"), Value::Null)]);
+    assert_eq!(keys(&map), ordered(&["b", "a", "c"]));
+    assert_eq!(map.get("a"), Some(&Value::Null));
+}
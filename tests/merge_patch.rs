@@ -0,0 +1,95 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::patch;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn merge(target: &str, patch: &str) -> Value {
+    let mut doc = value(target);
+    doc.merge_patch(&value(patch));
+    doc
+}
+
+// RFC 7396, appendix A.
+#[test]
+fn rfc_examples() {
+    let cases = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (r#"{"a":{"b":"c"}}"#, r#"{"a":{"b":"d","c":null}}"#, r#"{"a":{"b":"d"}}"#),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (r#"{}"#, r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+    ];
+    for (target, patch, expected) in cases {
+        assert_eq!(merge(target, patch), value(expected), "{} with {}", target, patch);
+    }
+}
+
+// RFC 7396, section 3.
+#[test]
+fn rfc_example_document() {
+    let target = r#"{
+        "title": "Goodbye!",
+        "author": {"givenName": "John", "familyName": "Doe"},
+        "tags": ["example", "sample"],
+        "content": "This will be unchanged"
+    }"#;
+    let patch = r#"{
+        "title": "Hello!",
+        "phoneNumber": "+01-123-456-7890",
+        "author": {"familyName": null},
+        "tags": ["example"]
+    }"#;
+    let expected = r#"{
+        "title": "Hello!",
+        "author": {"givenName": "John"},
+        "tags": ["example"],
+        "content": "This will be unchanged",
+        "phoneNumber": "+01-123-456-7890"
+    }"#;
+    assert_eq!(merge(target, patch), value(expected));
+}
+
+#[test]
+fn diff_round_trips() {
+    let cases = [
+        (r#"{"a": 1, "b": {"c": 2, "d": [1]}}"#, r#"{"b": {"c": 3, "d": [1, 2]}, "e": "x"}"#),
+        (r#"{"a": {"b": {"c": 1}}}"#, r#"{"a": {"b": {}}}"#),
+        (r#"{"a": 1}"#, r#"[1]"#),
+        (r#""x""#, r#"{"a": 1}"#),
+        (r#"{"a": 1}"#, r#"{"a": 1}"#),
+    ];
+    for (a, b) in cases {
+        let patch = patch::merge_patch_diff(&value(a), &value(b));
+        assert_eq!(merge(a, &patch.to_string()), value(b), "{} to {} with {}", a, b, patch);
+    }
+}
+
+#[test]
+fn diff_is_minimal() {
+    let patch = patch::merge_patch_diff(&value(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#), &value(r#"{"a": 1, "b": {"c": 2, "d": 4}}"#));
+    assert_eq!(patch, value(r#"{"b": {"d": 4}}"#));
+    let patch = patch::merge_patch_diff(&value(r#"{"a": [1, 2]}"#), &value(r#"{"a": [1, 3]}"#));
+    assert_eq!(patch, value(r#"{"a": [1, 3]}"#));
+    assert_eq!(patch::merge_patch_diff(&value(r#"{"a": 1}"#), &value(r#"{}"#)), value(r#"{"a": null}"#));
+}
+
+#[test]
+fn diff_cannot_keep_null_members() {
+    let (a, b) = (r#"{"a": 1}"#, r#"{"a": null}"#);
+    let patch = patch::merge_patch_diff(&value(a), &value(b));
+    assert_eq!(patch, value(r#"{"a": null}"#));
+    assert_eq!(merge(a, &patch.to_string()), value("{}"));
+}
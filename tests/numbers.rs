@@ -0,0 +1,122 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::value::Number;
+
+fn number(json: &str) -> Number {
+    match json::from_str(json) {
+        Ok(Value::Number(ref n)) => n.clone(),
+        other => panic!("{:?} parsed as {:?}", json, other),
+    }
+}
+
+fn error(json: &str) -> (ErrorKind, usize) {
+    match json::from_str(json) {
+        Ok(value) => panic!("{:?} parsed as {:?}", json, value),
+        Err(err) => (err.kind(), err.offset()),
+    }
+}
+
+fn rewrite(json: &str) -> String {
+    json::from_str(json).unwrap().to_string()
+}
+
+#[test]
+fn integers() {
+    assert_eq!(number("0").as_u64(), Some(0));
+    assert_eq!(number("43").as_u64(), Some(43));
+    assert_eq!(number("-43").as_i64(), Some(-43));
+    assert_eq!(number("18446744073709551615").as_u64(), Some(u64::MAX));
+    assert_eq!(number("-9223372036854775808").as_i64(), Some(i64::MIN));
+    // Past what an f64 holds exactly.
+    assert_eq!(number("9007199254740993").as_u64(), Some(9007199254740993));
+    assert!(!number("9007199254740993").is_f64());
+}
+
+#[test]
+fn integers_out_of_range() {
+    let n = number("18446744073709551616");
+    assert_eq!(n.as_u64(), None);
+    assert_eq!(n.as_f64(), Some(18446744073709551616.0));
+    let n = number("-9223372036854775809");
+    assert_eq!(n.as_i64(), None);
+    assert_eq!(n.as_f64(), Some(-9223372036854775809.0));
+}
+
+#[test]
+fn negative_zero() {
+    for json in ["-0", "-0.0", "-0e0"] {
+        let n = number(json);
+        assert!(n.is_f64(), "{}", json);
+        assert_eq!(n.as_u64(), None, "{}", json);
+        assert_eq!(n.as_i64(), None, "{}", json);
+        assert!(n.as_f64().unwrap().is_sign_negative(), "{}", json);
+    }
+    assert_eq!(number("0").as_u64(), Some(0));
+}
+
+#[test]
+fn floats_round_like_std() {
+    let inputs = [
+        "0.1",
+        "0.30000000000000004",
+        "1E2",
+        "1e-2",
+        "2.5e+3",
+        "-1.5E-7",
+        "123456789012345678901234567890",
+        "2.2250738585072011e-308",
+        "2.2250738585072014e-308",
+        "4.9e-324",
+        "1.7976931348623157e308",
+        "9007199254740993.0",
+        "0.1000000000000000055511151231257827021181583404541015625",
+    ];
+    for json in inputs {
+        assert_eq!(number(json).as_f64(), json.parse::<f64>().ok(), "{}", json);
+    }
+}
+
+#[test]
+fn underflow() {
+    assert_eq!(number("1e-400").as_f64(), Some(0.0));
+    assert_eq!(number("-1e-400").as_f64(), Some(-0.0));
+}
+
+#[test]
+fn overflow() {
+    assert_eq!(error("1e400"), (ErrorKind::InvalidNumber, 0));
+    assert_eq!(error("-1e400"), (ErrorKind::InvalidNumber, 0));
+    assert_eq!(error("[1, 1e400]"), (ErrorKind::InvalidNumber, 4));
+}
+
+#[test]
+fn malformed() {
+    assert_eq!(error("01"), (ErrorKind::InvalidNumber, 1));
+    assert_eq!(error("-01"), (ErrorKind::InvalidNumber, 2));
+    assert_eq!(error("1.x"), (ErrorKind::InvalidNumber, 2));
+    assert_eq!(error("1ex"), (ErrorKind::InvalidNumber, 2));
+    assert_eq!(error("-x"), (ErrorKind::InvalidNumber, 1));
+    assert_eq!(error(".5"), (ErrorKind::UnexpectedCharacter, 0));
+    assert_eq!(error("+1"), (ErrorKind::UnexpectedCharacter, 0));
+    assert_eq!(error("-"), (ErrorKind::UnexpectedEof, 1));
+    assert_eq!(error("1."), (ErrorKind::UnexpectedEof, 2));
+    assert_eq!(error("1e+"), (ErrorKind::UnexpectedEof, 3));
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+#[test]
+fn written() {
+    assert_eq!(rewrite("-0"), "-0.0");
+    assert_eq!(rewrite("1E2"), "100.0");
+    assert_eq!(rewrite("123456789012345678901234567890"), "1.2345678901234568e29");
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn written() {
+    for json in ["-0", "1E2", "1.000", "123456789012345678901234567890", "1e-400"] {
+        assert_eq!(rewrite(json), json);
+        assert_eq!(number(json).as_str(), json);
+    }
+}
@@ -0,0 +1,295 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::patch;
+use json_parser::json::patch::Patch;
+use json_parser::json::patch::PatchError;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn apply(doc: &str, patch: &str) -> Result<Value, PatchError> {
+    let mut doc = value(doc);
+    let original = doc.clone();
+    let patch: Patch = patch.parse()?;
+    match patch::apply(&mut doc, &patch) {
+        Ok(()) => {
+            Ok(doc)
+        }
+        Err(err) => {
+            assert_eq!(doc, original, "a failed patch changed the document");
+            Err(err)
+        }
+    }
+}
+
+// The examples of RFC 6902, appendix A.
+
+#[test]
+fn a1_adding_an_object_member() {
+    let doc = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"baz": "qux", "foo": "bar"}"#));
+}
+
+#[test]
+fn a2_adding_an_array_element() {
+    let doc = apply(
+        r#"{"foo": ["bar", "baz"]}"#,
+        r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": ["bar", "qux", "baz"]}"#));
+}
+
+#[test]
+fn a3_removing_an_object_member() {
+    let doc = apply(
+        r#"{"baz": "qux", "foo": "bar"}"#,
+        r#"[{"op": "remove", "path": "/baz"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": "bar"}"#));
+}
+
+#[test]
+fn a4_removing_an_array_element() {
+    let doc = apply(
+        r#"{"foo": ["bar", "qux", "baz"]}"#,
+        r#"[{"op": "remove", "path": "/foo/1"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": ["bar", "baz"]}"#));
+}
+
+#[test]
+fn a5_replacing_a_value() {
+    let doc = apply(
+        r#"{"baz": "qux", "foo": "bar"}"#,
+        r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"baz": "boo", "foo": "bar"}"#));
+}
+
+#[test]
+fn a6_moving_a_value() {
+    let doc = apply(
+        r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+        r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+    );
+    let expected = r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#;
+    assert_eq!(doc.unwrap(), value(expected));
+}
+
+#[test]
+fn a7_moving_an_array_element() {
+    let doc = apply(
+        r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+        r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": ["all", "cows", "eat", "grass"]}"#));
+}
+
+#[test]
+fn a8_testing_a_value_success() {
+    let doc = apply(
+        r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+        r#"[
+            {"op": "test", "path": "/baz", "value": "qux"},
+            {"op": "test", "path": "/foo/1", "value": 2}
+        ]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#));
+}
+
+#[test]
+fn a9_testing_a_value_error() {
+    let err = apply(
+        r#"{"baz": "qux"}"#,
+        r#"[{"op": "test", "path": "/baz", "value": "bar"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::TestFailed { operation: 0, .. })), "{:?}", err);
+}
+
+#[test]
+fn a10_adding_a_nested_member_object() {
+    let doc = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": "bar", "child": {"grandchild": {}}}"#));
+}
+
+#[test]
+fn a11_ignoring_unrecognized_elements() {
+    let doc = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": "bar", "baz": "qux"}"#));
+}
+
+#[test]
+fn a12_adding_to_a_nonexistent_target() {
+    let err = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz/bat", "value": "qux"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::Pointer { operation: 0, .. })), "{:?}", err);
+}
+
+#[test]
+fn a13_invalid_json_patch_document() {
+    let err = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": "qux", "op": "remove"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::Parse(ref err)) if err.kind() == ErrorKind::DuplicateKey), "{:?}", err);
+}
+
+#[test]
+fn repeated_keys_inside_values() {
+    // Only an operation's own members must be unique; its value is read
+    // like any other JSON, where the last occurrence wins.
+    let doc = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": {"a": 1, "a": 2, "b": [{"c": 3, "c": 4}]}}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": "bar", "baz": {"a": 2, "b": [{"c": 4}]}}"#));
+
+    let err = apply(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "test", "path": "/foo", "value": "bar"}, {"op": "remove", "path": "/foo", "path": "/baz"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::Parse(ref err)) if err.kind() == ErrorKind::DuplicateKey), "{:?}", err);
+}
+
+#[test]
+fn a14_escape_ordering() {
+    let doc = apply(
+        r#"{"/": 9, "~1": 10}"#,
+        r#"[{"op": "test", "path": "/~01", "value": 10}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"/": 9, "~1": 10}"#));
+}
+
+#[test]
+fn a15_comparing_strings_and_numbers() {
+    let err = apply(
+        r#"{"/": 9, "~1": 10}"#,
+        r#"[{"op": "test", "path": "/~01", "value": "10"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::TestFailed { operation: 0, .. })), "{:?}", err);
+}
+
+#[test]
+fn a16_adding_an_array_value() {
+    let doc = apply(
+        r#"{"foo": ["bar"]}"#,
+        r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#,
+    );
+    assert_eq!(doc.unwrap(), value(r#"{"foo": ["bar", ["abc", "def"]]}"#));
+}
+
+#[test]
+fn malformed_operations() {
+    let patches = [
+        r#"{"op": "add", "path": "/a", "value": 1}"#,
+        r#"[{"op": "launch", "path": "/a"}]"#,
+        r#"[{"op": "add", "path": "/a"}]"#,
+        r#"[{"op": "move", "path": "/a"}]"#,
+        r#"[{"path": "/a"}]"#,
+    ];
+    for patch in patches {
+        let err = patch.parse::<Patch>();
+        assert!(matches!(err, Err(PatchError::Syntax(_))), "{}: {:?}", patch, err);
+    }
+}
+
+#[test]
+fn failed_patch_is_atomic() {
+    let err = apply(
+        r#"{"a": 1, "b": [1, 2]}"#,
+        r#"[
+            {"op": "remove", "path": "/a"},
+            {"op": "add", "path": "/b/0", "value": 0},
+            {"op": "replace", "path": "/missing", "value": 0}
+        ]"#,
+    );
+    assert!(matches!(err, Err(PatchError::Pointer { operation: 2, .. })), "{:?}", err);
+}
+
+#[test]
+fn move_into_own_child() {
+    let err = apply(
+        r#"{"a": {"b": {}}}"#,
+        r#"[{"op": "move", "from": "/a", "path": "/a/b/c"}]"#,
+    );
+    assert!(matches!(err, Err(PatchError::MoveIntoChild { operation: 0, .. })), "{:?}", err);
+}
+
+#[test]
+fn patch_round_trips_through_json() {
+    let text = r#"[
+        {"op": "add", "path": "/a", "value": [1, {"b": null}]},
+        {"op": "remove", "path": "/c"},
+        {"op": "replace", "path": "/d", "value": "e"},
+        {"op": "move", "from": "/f", "path": "/g"},
+        {"op": "copy", "from": "/h", "path": "/i"},
+        {"op": "test", "path": "/j", "value": 1.5}
+    ]"#;
+    let patch: Patch = text.parse().unwrap();
+    assert_eq!(patch.operations().len(), 6);
+    assert_eq!(patch.to_string().parse::<Patch>().unwrap(), patch);
+    assert_eq!(patch.to_value(), value(text));
+}
+
+fn assert_diff(a: &Value, b: &Value) -> Patch {
+    let patch = patch::diff(a, b);
+    let mut doc = a.clone();
+    patch::apply(&mut doc, &patch).unwrap();
+    assert_eq!(&doc, b, "patch {}", patch);
+    patch
+}
+
+#[test]
+fn diff_round_trip() {
+    let pairs = [
+        ("null", "null"),
+        ("1", "2"),
+        ("{}", r#"{"a": 1}"#),
+        (r#"{"a": 1, "b": 2}"#, r#"{"b": 3, "c": 4}"#),
+        (r#"{"a": {"b": [1, 2, 3]}}"#, r#"{"a": {"b": [1, 3, 4]}}"#),
+        ("[1, 2, 3, 4, 5]", "[0, 1, 3, 5, 6]"),
+        ("[1, 2, 3]", "[]"),
+        ("[]", "[1, 2, 3]"),
+        ("[[1], [2]]", "[[2], [1]]"),
+        (r#"{"a": [1, 2]}"#, r#"{"a": {"0": 1}}"#),
+        (r#"{"~/": 1}"#, r#"{"~/": 2, "/~": 3}"#),
+    ];
+    for (a, b) in pairs {
+        assert_diff(&value(a), &value(b));
+        assert_diff(&value(b), &value(a));
+    }
+}
+
+#[test]
+fn diff_is_minimal_for_small_changes() {
+    assert!(patch::diff(&value(r#"{"a": [1, 2]}"#), &value(r#"{"a": [1, 2]}"#)).is_empty());
+
+    let patch = assert_diff(&value(r#"{"a": 1, "b": 2}"#), &value(r#"{"a": 1, "b": 3}"#));
+    assert_eq!(patch.to_value(), value(r#"[{"op": "replace", "path": "/b", "value": 3}]"#));
+
+    let patch = assert_diff(&value("[1, 2, 3, 4]"), &value("[1, 2, 4]"));
+    assert_eq!(patch.to_value(), value(r#"[{"op": "remove", "path": "/2"}]"#));
+}
+
+#[test]
+fn diff_of_large_arrays() {
+    // Large enough that the elements are paired by index rather than by
+    // edit distance.
+    let a: Vec<Value> = (0..3000u64).map(Value::from).collect();
+    let b: Vec<Value> = (0..3000u64).rev().chain(3000..3100).map(Value::from).collect();
+    assert_diff(&Value::Array(a.clone()), &Value::Array(b.clone()));
+    assert_diff(&Value::Array(b), &Value::Array(a));
+}
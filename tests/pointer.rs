@@ -0,0 +1,127 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::value::PointerError;
+use json_parser::json::value::pointer;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+// The example document from RFC 6901, section 5.
+fn rfc_document() -> Value {
+    value(r#"{
+        "foo": ["bar", "baz"],
+        "": 0,
+        "a/b": 1,
+        "c%d": 2,
+        "e^f": 3,
+        "g|h": 4,
+        "i\\j": 5,
+        "k\"l": 6,
+        " ": 7,
+        "m~n": 8
+    }"#)
+}
+
+#[test]
+fn rfc_examples() {
+    let doc = rfc_document();
+    let cases = [
+        ("/foo", r#"["bar", "baz"]"#),
+        ("/foo/0", r#""bar""#),
+        ("/", "0"),
+        ("/a~1b", "1"),
+        ("/c%d", "2"),
+        ("/e^f", "3"),
+        ("/g|h", "4"),
+        ("/i\\j", "5"),
+        ("/k\"l", "6"),
+        ("/ ", "7"),
+        ("/m~0n", "8"),
+    ];
+    for (pointer, expected) in cases {
+        assert_eq!(doc.pointer(pointer), Some(&value(expected)), "{:?}", pointer);
+    }
+    assert_eq!(doc.pointer(""), Some(&doc));
+}
+
+#[test]
+fn lookup_errors() {
+    let doc = value(r#"{"a": [1, {"b": 2}], "c": 3}"#);
+    let cases = [
+        ("a", PointerError::Syntax),
+        ("/a~2", PointerError::InvalidEscape),
+        ("/a~", PointerError::InvalidEscape),
+        ("/a/01", PointerError::InvalidIndex(String::from("01"))),
+        ("/a/-", PointerError::InvalidIndex(String::from("-"))),
+        ("/a/+1", PointerError::InvalidIndex(String::from("+1"))),
+        ("/a/2", PointerError::NotFound(String::from("2"))),
+        ("/d", PointerError::NotFound(String::from("d"))),
+        ("/c/0", PointerError::NotContainer(String::from("0"))),
+    ];
+    for (text, expected) in cases {
+        let result = pointer::parse_pointer(text).and_then(|tokens| pointer::resolve(&doc, &tokens).map(|_| ()));
+        assert_eq!(result, Err(expected), "{:?}", text);
+        assert_eq!(doc.pointer(text), None, "{:?}", text);
+    }
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(pointer::parse_pointer("/a~1b/~01/"), Ok(vec![String::from("a/b"), String::from("~1"), String::new()]));
+    assert_eq!(pointer::escape_token("~/"), "~0~1");
+    assert_eq!(pointer::to_pointer(&["a/b", "~", "0"]), "/a~1b/~0/0");
+    assert_eq!(pointer::to_pointer::<&str>(&[]), "");
+}
+
+#[test]
+fn pointer_mut() {
+    let mut doc = value(r#"{"a": [1, {"b": 2}]}"#);
+    *doc.pointer_mut("/a/1/b").unwrap() = Value::from(3);
+    assert_eq!(doc, value(r#"{"a": [1, {"b": 3}]}"#));
+    assert!(doc.pointer_mut("/a/2").is_none());
+}
+
+#[test]
+fn insert() {
+    let mut doc = value(r#"{"a": [1, 3]}"#);
+    assert_eq!(doc.pointer_insert("/a/1", Value::from(2)), Ok(None));
+    assert_eq!(doc.pointer_insert("/a/-", Value::from(4)), Ok(None));
+    assert_eq!(doc.pointer_insert("/a/4", Value::from(5)), Ok(None));
+    assert_eq!(doc.pointer_insert("/b", Value::from(true)), Ok(None));
+    assert_eq!(doc.pointer_insert("/b", Value::from(false)), Ok(Some(Value::from(true))));
+    assert_eq!(doc, value(r#"{"a": [1, 2, 3, 4, 5], "b": false}"#));
+
+    assert_eq!(doc.pointer_insert("/a/6", Value::Null), Err(PointerError::InvalidIndex(String::from("6"))));
+    assert_eq!(doc.pointer_insert("/a/x", Value::Null), Err(PointerError::InvalidIndex(String::from("x"))));
+    assert_eq!(doc.pointer_insert("/c/d", Value::Null), Err(PointerError::NotFound(String::from("c"))));
+    assert_eq!(doc.pointer_insert("/b/c", Value::Null), Err(PointerError::NotContainer(String::from("c"))));
+    assert_eq!(doc.pointer_insert("b", Value::Null), Err(PointerError::Syntax));
+    assert_eq!(doc, value(r#"{"a": [1, 2, 3, 4, 5], "b": false}"#));
+
+    let old = doc.pointer_insert("", Value::from(1)).unwrap();
+    assert_eq!(old, Some(value(r#"{"a": [1, 2, 3, 4, 5], "b": false}"#)));
+    assert_eq!(doc, Value::from(1));
+}
+
+#[test]
+fn remove() {
+    let mut doc = value(r#"{"a": [1, 2, 3], "b": {"c": null}}"#);
+    assert_eq!(doc.pointer_remove("/a/1"), Ok(Value::from(2)));
+    assert_eq!(doc.pointer_remove("/b/c"), Ok(Value::Null));
+    assert_eq!(doc, value(r#"{"a": [1, 3], "b": {}}"#));
+
+    assert_eq!(doc.pointer_remove("/a/2"), Err(PointerError::NotFound(String::from("2"))));
+    assert_eq!(doc.pointer_remove("/a/-"), Err(PointerError::InvalidIndex(String::from("-"))));
+    assert_eq!(doc.pointer_remove("/b/c"), Err(PointerError::NotFound(String::from("c"))));
+    assert_eq!(doc.pointer_remove("/a/0/x"), Err(PointerError::NotContainer(String::from("x"))));
+    assert_eq!(doc.pointer_remove(""), Err(PointerError::Root));
+    assert_eq!(doc, value(r#"{"a": [1, 3], "b": {}}"#));
+}
+
+#[test]
+fn error_messages() {
+    assert_eq!(PointerError::Syntax.to_string(), "JSON pointer must be empty or start with '/'");
+    assert_eq!(PointerError::NotFound(String::from("a")).to_string(), "\"a\" not found");
+    assert_eq!(PointerError::Root.to_string(), "cannot remove the root of the document");
+}
@@ -0,0 +1,141 @@
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::Value;
+use json_parser::json::de::PushParser;
+use json_parser::json::de::Status;
+
+// Feeds `chunks` in order, collecting every top-level value completed.
+fn push(chunks: &[&[u8]]) -> Result<Vec<Value>, Error> {
+    let mut parser = PushParser::new();
+    let mut values = Vec::new();
+    for chunk in chunks {
+        let mut status = parser.feed(chunk)?;
+        while let Status::Complete(value) = status {
+            values.push(value);
+            status = parser.feed(&[])?;
+        }
+    }
+    while let Some(value) = parser.finish()? {
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+// Every way of cutting `input` in two, and byte by byte.
+fn splits(input: &[u8]) -> Vec<Vec<&[u8]>> {
+    let mut splits: Vec<Vec<&[u8]>> = (0..=input.len())
+        .map(|at| vec![&input[..at], &input[at..]])
+        .collect();
+    splits.push(input.chunks(1).collect());
+    splits
+}
+
+const DOCUMENTS: &[&str] = &[
+    "null",
+    "true",
+    "false",
+    "0",
+    "-0",
+    "123",
+    "-12.5e-3",
+    "18446744073709551615",
+    "-9223372036854775809",
+    r#""""#,
+    r#""plain text""#,
+    r#""\" \\ \/ \b \f \n \r \t""#,
+    r#""\u0041\u00e9\u20AC \ud83d\ude00""#,
+    "\"caf\u{e9} \u{20ac} \u{1f600}\"",
+    "[]",
+    "{}",
+    " [ 1 , [ 2 , [ ] ] , { } ] ",
+    r#"{"a": {"b": [null, true, 1.5, "x"]}, "c": -7}"#,
+    "\t\r\n{\"key\u{e9}\": \"value\u{1f600}\"}\n",
+];
+
+#[test]
+fn documents_split_anywhere() {
+    for document in DOCUMENTS {
+        let expected = json::from_str(document).unwrap();
+        for chunks in splits(document.as_bytes()) {
+            let values = push(&chunks).unwrap_or_else(|err| panic!("{:?} in {:?}: {}", chunks, document, err));
+            assert_eq!(values.len(), 1, "{:?}", chunks);
+            assert_eq!(values[0], expected, "{:?}", chunks);
+        }
+    }
+}
+
+#[test]
+fn several_documents_split_anywhere() {
+    let input = r#"{"a": 1} [2, 3] "four"5 null 6.5 {"b": [7]}"#;
+    let expected: Vec<Value> = json::StreamDeserializer::new(input)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(expected.len(), 7);
+    for chunks in splits(input.as_bytes()) {
+        assert_eq!(push(&chunks).unwrap(), expected, "{:?}", chunks);
+    }
+}
+
+// Compared with `from_reader`, which also decodes the input byte by byte.
+#[test]
+fn errors_split_anywhere() {
+    let documents: &[&[u8]] = &[
+        b"[1, 2,]",
+        b"{\"a\" 1}",
+        b"{\"a\": 1,}",
+        b"[1 2]",
+        b"01",
+        b"-",
+        b"1.e5",
+        b"tru",
+        b"nul!",
+        b"\"abc",
+        b"\"a\x01\"",
+        b"\"\\x\"",
+        b"\"\\ud800\"",
+        b"\"\\u12g4\"",
+        b"\"\xc0\xaf\"",
+        b"\"ab\xed\xa0\x80\"",
+        b"[\"\xe2\x82\"]",
+        b"1e400",
+        b"{\"a\": [}",
+    ];
+    for document in documents {
+        let expected = json::from_reader(*document).unwrap_err();
+        for chunks in splits(document) {
+            let err = push(&chunks).unwrap_err();
+            assert_eq!(
+                (err.kind(), err.position()),
+                (expected.kind(), expected.position()),
+                "{:?} split as {:?}",
+                String::from_utf8_lossy(document),
+                chunks,
+            );
+        }
+    }
+}
+
+#[test]
+fn scalars_need_a_delimiter() {
+    for document in ["nulltrue", "1true", "true1", "1 nullfalse", "falsex", "-2.5e1null"] {
+        let expected = json::StreamDeserializer::new(document)
+            .find_map(Result::err)
+            .unwrap();
+        for chunks in splits(document.as_bytes()) {
+            let err = push(&chunks).unwrap_err();
+            assert_eq!((err.kind(), err.position()), (expected.kind(), expected.position()), "{:?}", chunks);
+        }
+    }
+    for chunks in splits(b"null true\nfalse[1]2\"a\"3{}") {
+        assert_eq!(push(&chunks).unwrap().len(), 8, "{:?}", chunks);
+    }
+}
+
+#[test]
+fn errors_are_sticky() {
+    let mut parser = PushParser::new();
+    assert!(parser.feed(b"[1,,").is_err());
+    assert!(parser.feed(b"2]").is_err());
+    assert!(parser.finish().is_err());
+}
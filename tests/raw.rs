@@ -0,0 +1,98 @@
+use json_parser::json;
+use json_parser::json::RawValue;
+use json_parser::json::Value;
+use json_parser::json::de::De;
+use json_parser::json::de::DuplicateKeys;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+use json_parser::json::value::PointerError;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+// Parses `json` keeping each of `pointers` raw.
+fn parse(json: &str, pointers: &[&str]) -> Value {
+    let mut de = De::new(json);
+    for pointer in pointers {
+        de = de.raw_pointer(pointer).unwrap();
+    }
+    de.deserialization().unwrap_or_else(|err| panic!("{:?}: {}", json, err))
+}
+
+fn raw_text(value: &Value) -> &str {
+    match *value {
+        Value::Raw(ref raw) => raw.get(),
+        ref other => panic!("not raw: {:?}", other),
+    }
+}
+
+#[test]
+fn selected_paths_stay_raw() {
+    let json = r#"{"route": "a", "body": {"x": [1,   2], "y": "é"}, "list": [{"n": 1}, {"n":2}]}"#;
+    let doc = parse(json, &["/body", "/list/1"]);
+    assert_eq!(doc["route"], Value::from("a"));
+    assert_eq!(raw_text(&doc["body"]), r#"{"x": [1,   2], "y": "é"}"#);
+    assert_eq!(doc["list"][0], value(r#"{"n": 1}"#));
+    assert_eq!(raw_text(&doc["list"][1]), r#"{"n":2}"#);
+}
+
+#[test]
+fn scalars_and_the_root() {
+    let doc = parse(r#"{"a": "s\n", "b": -1.50e1, "c": null}"#, &["/a", "/b", "/c"]);
+    assert_eq!(raw_text(&doc["a"]), r#""s\n""#);
+    assert_eq!(raw_text(&doc["b"]), "-1.50e1");
+    assert_eq!(raw_text(&doc["c"]), "null");
+    assert_eq!(raw_text(&parse(" [1, 2] ", &[""])), "[1, 2]");
+}
+
+#[test]
+fn unmatched_pointers_change_nothing() {
+    let json = r#"{"a": [1, 2]}"#;
+    assert_eq!(parse(json, &["/b", "/a/5", "/a/0/x"]), value(json));
+    assert_eq!(De::new(json).raw_pointer("a").err(), Some(PointerError::Syntax));
+}
+
+#[test]
+fn raw_text_is_still_checked() {
+    let err = De::new(r#"{"a": [1, }"#).raw_pointer("/a").unwrap().deserialization().unwrap_err();
+    assert_eq!((err.kind(), err.offset()), (ErrorKind::UnexpectedCharacter, 10));
+    // Though not for repeated keys.
+    let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+    let json = r#"{"a": {"k": 1, "k": 2}}"#;
+    let doc = De::with_options(json, options).raw_pointer("/a").unwrap().deserialization().unwrap();
+    assert_eq!(raw_text(&doc["a"]), r#"{"k": 1, "k": 2}"#);
+}
+
+#[test]
+fn spliced_verbatim() {
+    let doc = parse(r#"{"keep": {"b":  2,"a" :1}, "other": [ 3 ]}"#, &["/keep"]);
+    assert_eq!(doc.to_string(), r#"{"keep":{"b":  2,"a" :1},"other":[3]}"#);
+    assert_eq!(json::to_string_pretty(&doc).unwrap(), "{\n  \"keep\": {\"b\":  2,\"a\" :1},\n  \"other\": [\n    3\n  ]\n}");
+
+    let mut built = value(r#"{"id": 1}"#);
+    built["payload"] = Value::Raw("[true, false]".parse().unwrap());
+    assert_eq!(built.to_string(), r#"{"id":1,"payload":[true, false]}"#);
+}
+
+#[test]
+fn from_string() {
+    let raw: RawValue = " {\"a\": [1]} ".parse().unwrap();
+    assert_eq!(raw.get(), " {\"a\": [1]} ");
+    assert_eq!(raw.to_string(), " {\"a\": [1]} ");
+    assert_eq!(raw.to_value().unwrap(), value(r#"{"a": [1]}"#));
+    assert_eq!(raw.clone().into_string(), " {\"a\": [1]} ");
+
+    for json in ["", "[1,]", "1 2", "{\"a\"}", "nul"] {
+        assert!(RawValue::from_string(String::from(json)).is_err(), "{:?}", json);
+    }
+}
+
+#[test]
+fn equality() {
+    let a: RawValue = "[1, 2]".parse().unwrap();
+    let b: RawValue = "[1,2]".parse().unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a.to_value().unwrap(), b.to_value().unwrap());
+    assert_eq!(Value::Raw(a.clone()), Value::Raw(a));
+}
@@ -0,0 +1,76 @@
+use std::io;
+
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::Value;
+use json_parser::json::de::De;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+
+// Hands out its input one byte per read, splitting every character.
+struct ByteReader<'a>(&'a [u8]);
+
+impl io::Read for ByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.split_first() {
+            Some((&byte, rest)) if !buf.is_empty() => {
+                buf[0] = byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => {
+                Ok(0)
+            }
+        }
+    }
+}
+
+// Parses `json` through each entry point, which must agree.
+fn parse(json: &str) -> Result<Value, (ErrorKind, usize)> {
+    let from_str = json::from_str(json);
+    let results = [
+        json::from_slice(json.as_bytes()),
+        json::from_reader(json.as_bytes()),
+        json::from_reader(ByteReader(json.as_bytes())),
+    ];
+    for result in results {
+        assert_eq!(result, from_str, "{:?}", json);
+    }
+    from_str.map_err(|err: Error| (err.kind(), err.offset()))
+}
+
+#[test]
+fn entry_points_agree() {
+    let json = "{\"a\": [1, -2.5e3, true, null], \"caf\u{e9}\": \"\u{1f600} \\u00e9\"}";
+    let value = parse(json).unwrap();
+    assert_eq!(value["caf\u{e9}"], Value::from("\u{1f600} \u{e9}"));
+    assert_eq!(parse("  \r\n 7 \t\n").unwrap(), Value::from(7));
+}
+
+#[test]
+fn errors_agree() {
+    assert_eq!(parse(""), Err((ErrorKind::UnexpectedEof, 0)));
+    assert_eq!(parse("   "), Err((ErrorKind::UnexpectedEof, 3)));
+    assert_eq!(parse("[1] 2"), Err((ErrorKind::TrailingCharacters, 4)));
+    assert_eq!(parse("[\"\u{e9}\", ?]"), Err((ErrorKind::UnexpectedCharacter, 7)));
+    assert_eq!(parse("\u{feff}[]"), Err((ErrorKind::UnexpectedCharacter, 0)));
+}
+
+#[test]
+fn invalid_utf8() {
+    for bytes in [&b"\"\xc3\""[..], b"\"\xe2\x82\"", b"\"\xed\xa0\x80\"", b"\"\xf8\""] {
+        let err = json::from_slice(bytes).unwrap_err();
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::InvalidUtf8, 1), "{:?}", bytes);
+        let err = json::from_reader(ByteReader(bytes)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidUtf8, "{:?}", bytes);
+    }
+}
+
+#[test]
+fn reader_with_options() {
+    let options = ParserOptions::new().comments(true).max_depth(2);
+    let value = De::from_reader_with(&b"[[1]] // done"[..], options.clone()).deserialization().unwrap();
+    assert_eq!(value, json::from_str("[[1]]").unwrap());
+    let err = De::from_reader_with(&b"[[[1]]]"[..], options).deserialization().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DepthExceeded);
+}
@@ -0,0 +1,179 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::de::De;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn parse(json: &str, options: ParserOptions) -> Result<Value, ErrorKind> {
+    De::with_options(json, options).deserialization().map_err(|err| err.kind())
+}
+
+// `json` parses to `expected` with `options`, and is refused without them.
+fn assert_relaxed(options: ParserOptions, json: &str, expected: &str) {
+    assert_eq!(parse(json, options), Ok(value(expected)), "{}", json);
+    assert!(parse(json, ParserOptions::new()).is_err(), "{} is strict JSON", json);
+}
+
+fn all() -> ParserOptions {
+    ParserOptions::new()
+        .comments(true)
+        .trailing_commas(true)
+        .single_quotes(true)
+        .unquoted_keys(true)
+        .nan_infinity(true)
+        .hex_numbers(true)
+        .leading_plus(true)
+        .leading_decimal_point(true)
+}
+
+#[test]
+fn strict_by_default() {
+    assert!(!ParserOptions::new().is_relaxed());
+    assert!(all().is_relaxed());
+    assert!(ParserOptions::new().hex_numbers(true).is_relaxed());
+}
+
+#[test]
+fn comments() {
+    let options = ParserOptions::new().comments(true);
+    assert_relaxed(options.clone(), "// leading\n[1, // one\n 2 /* two */]", "[1, 2]");
+    assert_relaxed(options.clone(), "{/* a */\"a\"/**/:/***/1}/* end */", r#"{"a": 1}"#);
+    assert_relaxed(options.clone(), "1 // no newline at the end", "1");
+    assert_eq!(parse("[1 /* unclosed", options.clone()), Err(ErrorKind::UnexpectedEof));
+    assert_eq!(parse("[1 / 2]", options.clone()), Err(ErrorKind::UnexpectedCharacter));
+    // Only where whitespace may appear.
+    assert_eq!(parse(r#""a // b""#, options), Ok(value(r#""a // b""#)));
+    assert_eq!(parse("// x\n1", ParserOptions::new()), Err(ErrorKind::UnexpectedCharacter));
+}
+
+#[test]
+fn trailing_commas() {
+    let options = ParserOptions::new().trailing_commas(true);
+    assert_relaxed(options.clone(), "[1, 2,]", "[1, 2]");
+    assert_relaxed(options.clone(), r#"{"a": 1,}"#, r#"{"a": 1}"#);
+    assert_relaxed(options.clone(), "[[],]", "[[]]");
+    // A comma still has to follow something.
+    for json in ["[,]", "[1,,]", "[1,,2]", "{,}", r#"{"a": 1,,}"#] {
+        assert_eq!(parse(json, options.clone()), Err(ErrorKind::UnexpectedCharacter), "{}", json);
+    }
+}
+
+#[test]
+fn single_quotes() {
+    let options = ParserOptions::new().single_quotes(true);
+    assert_relaxed(options.clone(), "'a'", r#""a""#);
+    assert_relaxed(options.clone(), r#"{'a': 'say "hi"'}"#, r#"{"a": "say \"hi\""}"#);
+    assert_relaxed(options.clone(), r"'it\'s'", r#""it's""#);
+    // `\'` is also accepted in double-quoted strings.
+    assert_relaxed(options.clone(), r#""it\'s""#, r#""it's""#);
+    assert_eq!(parse("'a\"", options), Err(ErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn unquoted_keys() {
+    let options = ParserOptions::new().unquoted_keys(true);
+    assert_relaxed(options.clone(), "{a: 1, _b2: 2, $c: 3}", r#"{"a": 1, "_b2": 2, "$c": 3}"#);
+    assert_eq!(parse("{2a: 1}", options.clone()), Err(ErrorKind::UnexpectedCharacter));
+    // Only keys; values must still be quoted.
+    assert_eq!(parse("{a: b}", options), Err(ErrorKind::UnexpectedCharacter));
+}
+
+#[test]
+fn nan_infinity() {
+    let options = ParserOptions::new().nan_infinity(true);
+    let list = parse("[NaN, Infinity, -Infinity]", options.clone()).unwrap();
+    let floats: Vec<f64> = (0..3).map(|i| list[i].as_number().unwrap().as_f64().unwrap()).collect();
+    assert!(floats[0].is_nan());
+    assert_eq!(floats[1], f64::INFINITY);
+    assert_eq!(floats[2], f64::NEG_INFINITY);
+    // Written out as `null`, which is all JSON has for them.
+    assert_eq!(list.to_string(), "[null,null,null]");
+    assert!(parse("NaN", ParserOptions::new()).is_err());
+    assert!(parse("-NaN", options.clone()).is_ok());
+    assert!(parse("Inf", options).is_err());
+}
+
+#[test]
+fn hex_numbers() {
+    let options = ParserOptions::new().hex_numbers(true);
+    assert_relaxed(options.clone(), "[0x1F, 0XfF, -0x10, 0x0]", "[31, 255, -16, 0]");
+    assert_relaxed(options.clone(), "0xffffffffffffffff", "18446744073709551615");
+    assert_relaxed(options.clone(), "-0x8000000000000000", "-9223372036854775808");
+    let cases = [
+        ("0x10000000000000000", ErrorKind::InvalidNumber),
+        ("-0x8000000000000001", ErrorKind::InvalidNumber),
+        ("-0xffffffffffffffff", ErrorKind::InvalidNumber),
+        ("0x", ErrorKind::UnexpectedEof),
+        ("0xg", ErrorKind::InvalidNumber),
+        ("0x1.5", ErrorKind::TrailingCharacters),
+    ];
+    for (json, kind) in cases {
+        assert_eq!(parse(json, options.clone()), Err(kind), "{}", json);
+    }
+}
+
+#[test]
+fn leading_plus() {
+    let options = ParserOptions::new().leading_plus(true);
+    assert_relaxed(options.clone(), "[+1, +2.5, +0]", "[1, 2.5, 0]");
+    assert!(parse("+-1", options.clone()).is_err());
+    assert!(parse("++1", options).is_err());
+}
+
+#[test]
+fn leading_decimal_point() {
+    let options = ParserOptions::new().leading_decimal_point(true);
+    assert_relaxed(options.clone(), "[.5, -.25, .1e1]", "[0.5, -0.25, 1.0]");
+    assert!(parse(".", options.clone()).is_err());
+    assert!(parse(".e1", options).is_err());
+}
+
+#[test]
+fn toggles_are_independent() {
+    let inputs = [
+        "[1, /* c */ 2]",
+        "[1,]",
+        "'a'",
+        "{a: 1}",
+        "NaN",
+        "0x1",
+        "+1",
+        ".5",
+    ];
+    for (i, json) in inputs.iter().enumerate() {
+        assert!(parse(json, all()).is_ok(), "{}", json);
+        for (j, other) in inputs.iter().enumerate() {
+            let options = match j {
+                0 => ParserOptions::new().comments(true),
+                1 => ParserOptions::new().trailing_commas(true),
+                2 => ParserOptions::new().single_quotes(true),
+                3 => ParserOptions::new().unquoted_keys(true),
+                4 => ParserOptions::new().nan_infinity(true),
+                5 => ParserOptions::new().hex_numbers(true),
+                6 => ParserOptions::new().leading_plus(true),
+                _ => ParserOptions::new().leading_decimal_point(true),
+            };
+            assert_eq!(parse(json, options).is_ok(), i == j, "{} with the option for {}", json, other);
+        }
+    }
+}
+
+#[test]
+fn raw_capture_is_rewritten_as_json() {
+    let json = "{a: /* c */ {'x': [0x10, +1, .5, NaN,], y: 'it\\'s'}, b: 1,}";
+    let doc = De::with_options(json, all()).raw_pointer("/a").unwrap().deserialization().unwrap();
+    match doc["a"] {
+        Value::Raw(ref raw) => assert_eq!(raw.get(), r#"{"x":[16,1,0.5,null],"y":"it's"}"#),
+        ref other => panic!("not raw: {:?}", other),
+    }
+    assert_eq!(doc["a"], doc["a"]);
+    assert_eq!(value(&doc.to_string()), value(r#"{"a": {"x": [16, 1, 0.5, null], "y": "it's"}, "b": 1}"#));
+
+    // Strict input keeps its text exactly.
+    let doc = De::new(r#"{"a": [1,  2 ]}"#).raw_pointer("/a").unwrap().deserialization().unwrap();
+    assert_eq!(doc.to_string(), r#"{"a":[1,  2 ]}"#);
+}
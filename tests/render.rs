@@ -0,0 +1,109 @@
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::de::De;
+use json_parser::json::de::DuplicateKeys;
+use json_parser::json::de::ParserOptions;
+use json_parser::json::de::Position;
+
+fn error(json: &str) -> Error {
+    match json::from_str(json) {
+        Ok(value) => panic!("{:?} parsed as {:?}", json, value),
+        Err(err) => err,
+    }
+}
+
+fn position(json: &str) -> (usize, usize, usize) {
+    let err = error(json);
+    (err.line(), err.column(), err.offset())
+}
+
+#[test]
+fn lines_and_columns() {
+    assert_eq!(position("[1, x]"), (1, 5, 4));
+    assert_eq!(position("[1,\n  x]"), (2, 3, 6));
+    assert_eq!(position("{\"a\": 1,\r\n \"b\": }"), (2, 7, 16));
+    // Columns count characters, offsets count bytes.
+    assert_eq!(position("[\"\u{e9}\u{e9}\", x]"), (1, 8, 9));
+    assert_eq!(position("\n\n"), (3, 1, 2));
+}
+
+#[test]
+fn position_at() {
+    let json = "[1,\n\u{e9}x]";
+    assert_eq!(Position::at(json, 0), Position::new());
+    assert_eq!(Position::at(json, 6), Position {
+        offset: 6,
+        line: 2,
+        column: 2,
+    });
+    assert_eq!(Position::at(json, 6).to_string(), "line 2 column 2");
+}
+
+#[test]
+fn render() {
+    let json = r#"{"a" 1}"#;
+    assert_eq!(error(json).render(json), concat!(
+        "error: unexpected character, expected ':', found '1'\n",
+        " --> line 1 column 6\n",
+        "  |\n",
+        "1 | {\"a\" 1}\n",
+        "  |      ^\n",
+    ));
+}
+
+#[test]
+fn render_later_lines() {
+    let json = "[\r\n1,\r\n2,\r\n3,\r\n4,\r\n5,\r\n6,\r\n7,\r\n8,\r\n  x]";
+    assert_eq!(error(json).render(json), concat!(
+        "error: unexpected character, expected value, found 'x'\n",
+        "  --> line 10 column 3\n",
+        "   |\n",
+        "10 |   x]\n",
+        "   |   ^\n",
+    ));
+}
+
+#[test]
+fn render_keeps_tabs_and_counts_characters() {
+    let json = "\t[\u{e9}\u{e9}]";
+    assert_eq!(error(json).render(json), concat!(
+        "error: unexpected character, expected value, found '\u{e9}'\n",
+        " --> line 1 column 3\n",
+        "  |\n",
+        "1 | \t[\u{e9}\u{e9}]\n",
+        "  | \t ^\n",
+    ));
+}
+
+#[test]
+fn render_at_the_end() {
+    let json = "[1,\n";
+    assert_eq!(error(json).render(json), concat!(
+        "error: unexpected end of input, expected value\n",
+        " --> line 2 column 1\n",
+        "  |\n",
+        "2 | \n",
+        "  | ^\n",
+    ));
+}
+
+#[test]
+fn render_previous_occurrence() {
+    let json = "{\"a\": 1,\n \"a\": 2}";
+    let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+    let err = De::with_options(json, options).deserialization().unwrap_err();
+    assert_eq!(err.to_string(), "duplicate key: \"a\" at line 2 column 2, previously at line 1 column 2");
+    assert_eq!(err.render(json), concat!(
+        "error: duplicate key: \"a\"\n",
+        " --> line 2 column 2\n",
+        "  |\n",
+        "2 |  \"a\": 2}\n",
+        "  |  ^\n",
+        "  = note: previously at line 1 column 2\n",
+    ));
+}
+
+#[test]
+fn render_without_position() {
+    assert_eq!(Error::custom("bad").render("[]"), "error: invalid data: bad\n");
+}
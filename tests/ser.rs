@@ -0,0 +1,126 @@
+use std::io;
+
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::ser;
+use json_parser::json::ser::NonFinite;
+use json_parser::json::ser::Ser;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn write(ser: Ser<Vec<u8>>, value: &Value) -> Result<String, ser::Error> {
+    ser::to_string_with(ser, value)
+}
+
+#[test]
+fn compact() {
+    let json = r#"{ "a" : [ 1 , -2 , 0.5 , true , false , null ] , "b" : { } , "c" : [ ] , "d" : "x" }"#;
+    let written = json::to_string(&value(json)).unwrap();
+    assert_eq!(written, r#"{"a":[1,-2,0.5,true,false,null],"b":{},"c":[],"d":"x"}"#);
+    assert_eq!(value(json).to_string(), written);
+}
+
+#[test]
+fn pretty() {
+    let written = json::to_string_pretty(&value(r#"{"a": [1, {"b": null}], "c": {}, "d": []}"#)).unwrap();
+    assert_eq!(written, "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": {},\n  \"d\": []\n}");
+    assert_eq!(format!("{:#}", value("[1]")), "[\n  1\n]");
+    assert_eq!(json::to_string_pretty(&value("7")).unwrap(), "7");
+}
+
+#[test]
+fn custom_indent() {
+    let ser = Ser::new(Vec::new()).indent("\t");
+    assert_eq!(write(ser, &value(r#"{"a": [1]}"#)).unwrap(), "{\n\t\"a\": [\n\t\t1\n\t]\n}");
+    let ser = Ser::new(Vec::new()).indent("");
+    assert_eq!(write(ser, &value("[1, 2]")).unwrap(), "[\n1,\n2\n]");
+}
+
+#[test]
+fn strings() {
+    let cases = [
+        ("plain", r#""plain""#),
+        ("say \"hi\" \\ bye", r#""say \"hi\" \\ bye""#),
+        ("\u{8}\u{c}\n\r\t", r#""\b\f\n\r\t""#),
+        ("\u{0}\u{1f}\u{7f}", "\"\\u0000\\u001f\u{7f}\""),
+        // Only what JSON requires is escaped.
+        ("/ \u{e9} \u{2028} \u{1f600}", "\"/ \u{e9} \u{2028} \u{1f600}\""),
+    ];
+    for (str, expected) in cases {
+        let written = json::to_string(&Value::from(str)).unwrap();
+        assert_eq!(written, expected, "{:?}", str);
+        assert_eq!(value(&written), Value::from(str));
+    }
+    let written = json::to_string(&value(r#"{"a\nb": 1}"#)).unwrap();
+    assert_eq!(written, r#"{"a\nb":1}"#);
+}
+
+#[test]
+fn numbers() {
+    let list = Value::Array(vec![
+        Value::from(u64::MAX),
+        Value::from(i64::MIN),
+        Value::from(0.1),
+        Value::from(-2.0),
+        Value::from(1e300),
+        Value::from(5e-324),
+    ]);
+    let written = json::to_string(&list).unwrap();
+    assert_eq!(written, "[18446744073709551615,-9223372036854775808,0.1,-2.0,1e300,5e-324]");
+    assert_eq!(value(&written), list);
+}
+
+#[test]
+fn non_finite() {
+    let list = Value::Array(vec![Value::from(f64::NAN), Value::from(f64::INFINITY), Value::from(f64::NEG_INFINITY)]);
+    assert_eq!(json::to_string(&list).unwrap(), "[null,null,null]");
+    let ser = Ser::new(Vec::new()).non_finite(NonFinite::Null);
+    assert_eq!(write(ser, &list).unwrap(), "[null,null,null]");
+
+    for n in [f64::INFINITY, f64::NEG_INFINITY] {
+        let ser = Ser::new(Vec::new()).non_finite(NonFinite::Error);
+        match write(ser, &Value::from(n)) {
+            Err(ser::Error::NonFinite(found)) => assert_eq!(found, n),
+            other => panic!("{}: {:?}", n, other),
+        }
+    }
+    let ser = Ser::pretty(Vec::new()).non_finite(NonFinite::Error);
+    let err = write(ser, &Value::Array(vec![Value::from(1), Value::from(f64::NAN)])).unwrap_err();
+    assert_eq!(err.to_string(), "cannot serialize non-finite number NaN");
+}
+
+#[test]
+fn raw_values_are_written_as_is() {
+    let doc = json::de::De::new(r#"{"a": [1,  2 ], "b": 3}"#).raw_pointer("/a").unwrap().deserialization().unwrap();
+    assert_eq!(json::to_string(&doc).unwrap(), r#"{"a":[1,  2 ],"b":3}"#);
+}
+
+struct Failing;
+
+impl io::Write for Failing {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writers() {
+    let doc = value(r#"{"a": [1, 2]}"#);
+    let mut written = Vec::new();
+    json::to_writer(&mut written, &doc).unwrap();
+    assert_eq!(written, br#"{"a":[1,2]}"#);
+    let mut written = Vec::new();
+    ser::to_writer_pretty(&mut written, &doc).unwrap();
+    assert_eq!(written, json::to_string_pretty(&doc).unwrap().as_bytes());
+
+    match json::to_writer(Failing, &doc) {
+        Err(ser::Error::Io(ref err)) => assert_eq!(err.kind(), io::ErrorKind::BrokenPipe),
+        other => panic!("{:?}", other),
+    }
+}
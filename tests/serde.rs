@@ -0,0 +1,192 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::Parser;
+use json_parser::json::de::StrRead;
+use json_parser::json::ser::NonFinite;
+use json_parser::json::ser::Ser;
+use json_parser::json::serde::Deserializer;
+use json_parser::json::serde::Serializer;
+use json_parser::json::serde::from_reader;
+use json_parser::json::serde::from_slice;
+use json_parser::json::serde::from_str;
+use json_parser::json::serde::to_string;
+use json_parser::json::serde::to_string_pretty;
+use json_parser::json::serde::to_writer;
+use serde::Serialize;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+    label: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(Point, Point),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Borrowed<'a> {
+    name: &'a str,
+}
+
+fn point(x: i32, y: i32) -> Point {
+    Point { x, y, label: None }
+}
+
+#[test]
+fn structs_round_trip() {
+    let json = r#"{"x":1,"y":-2,"label":"origin"}"#;
+    let expected = Point { x: 1, y: -2, label: Some(String::from("origin")) };
+    assert_eq!(from_str::<Point>(json).unwrap(), expected);
+    assert_eq!(to_string(&expected).unwrap(), json);
+    assert_eq!(to_string(&point(0, 0)).unwrap(), r#"{"x":0,"y":0,"label":null}"#);
+    // A missing option reads as `None`.
+    assert_eq!(from_str::<Point>(r#"{"y": 0, "x": 0}"#).unwrap(), point(0, 0));
+}
+
+#[test]
+fn enums_are_externally_tagged() {
+    let cases = [
+        (Shape::Empty, r#""Empty""#),
+        (Shape::Circle(1.5), r#"{"Circle":1.5}"#),
+        (Shape::Line(point(0, 0), point(1, 1)), r#"{"Line":[{"x":0,"y":0,"label":null},{"x":1,"y":1,"label":null}]}"#),
+        (Shape::Rect { width: 2, height: 3 }, r#"{"Rect":{"width":2,"height":3}}"#),
+    ];
+    for (shape, json) in cases {
+        assert_eq!(to_string(&shape).unwrap(), json, "{:?}", shape);
+        assert_eq!(from_str::<Shape>(json).unwrap(), shape, "{}", json);
+    }
+    assert!(from_str::<Shape>("1").is_err());
+    assert!(from_str::<Shape>(r#"{"Circle": 1, "Empty": null}"#).is_err());
+}
+
+#[test]
+fn collections() {
+    let list: Vec<Option<u8>> = from_str("[1, null, 3]").unwrap();
+    assert_eq!(list, [Some(1), None, Some(3)]);
+    let map: BTreeMap<String, Vec<bool>> = from_str(r#"{"b": [true], "a": []}"#).unwrap();
+    assert_eq!(to_string(&map).unwrap(), r#"{"a":[],"b":[true]}"#);
+    let tuple: (u8, String, ()) = from_str(r#"[1, "two", null]"#).unwrap();
+    assert_eq!(tuple, (1, String::from("two"), ()));
+    // Non-string keys are written as their text.
+    let map = BTreeMap::from([(1u32, true), (20, false)]);
+    assert_eq!(to_string(&map).unwrap(), r#"{"1":true,"20":false}"#);
+    let map = BTreeMap::from([(vec![1u8], true)]);
+    assert_eq!(to_string(&map).unwrap_err().to_string(), "map key must be a string");
+}
+
+#[test]
+fn strings_borrow_from_the_input() {
+    let borrowed: Borrowed = from_str(r#"{"name": "plain"}"#).unwrap();
+    assert_eq!(borrowed.name, "plain");
+    // An escaped string has no unbroken text to borrow.
+    assert!(from_str::<Borrowed>(r#"{"name": "esc\naped"}"#).is_err());
+    let owned: String = from_str(r#""esc\naped""#).unwrap();
+    assert_eq!(owned, "esc\naped");
+}
+
+#[test]
+fn inputs_agree() {
+    let json = r#"{"x": 5, "y": 6, "label": "café"}"#;
+    let from_text: Point = from_str(json).unwrap();
+    assert_eq!(from_slice::<Point>(json.as_bytes()).unwrap(), from_text);
+    assert_eq!(from_reader::<_, Point>(json.as_bytes()).unwrap(), from_text);
+    assert_eq!(from_text.label.as_deref(), Some("caf\u{e9}"));
+}
+
+#[test]
+fn errors_are_positioned() {
+    let err = from_str::<Point>("{\n  \"x\": 1,\n  \"y\": \"two\"\n}").unwrap_err();
+    assert_eq!((err.line(), err.column()), (3, 8), "{}", err);
+    let err = from_str::<Point>(r#"{"x": 1}"#).unwrap_err();
+    assert!(err.to_string().contains("missing field `y`"), "{}", err);
+    let err = from_str::<u8>("256").unwrap_err();
+    assert_eq!(err.offset(), 0, "{}", err);
+    let err = from_str::<Vec<u8>>("[1, 2").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn trailing_characters() {
+    assert_eq!(from_str::<u8>("1 2").unwrap_err().kind(), ErrorKind::TrailingCharacters);
+    assert_eq!(from_reader::<_, u8>(&b"1 x"[..]).unwrap_err().kind(), ErrorKind::TrailingCharacters);
+
+    // Without `end`, whatever follows the value is left alone.
+    let mut deserializer = Deserializer::new("[1] [2]");
+    let first: Vec<u8> = serde::Deserialize::deserialize(&mut deserializer).unwrap();
+    assert_eq!(first, [1]);
+    assert_eq!(deserializer.end().unwrap_err().kind(), ErrorKind::TrailingCharacters);
+    let mut deserializer = Deserializer::new("[1]  \n");
+    let _: Vec<u8> = serde::Deserialize::deserialize(&mut deserializer).unwrap();
+    assert!(deserializer.end().is_ok());
+}
+
+#[test]
+fn deserializer_over_a_parser() {
+    let parser = Parser::new(StrRead::new(r#"{"x": 1, "y": 2}"#));
+    let mut deserializer = Deserializer::from_parser(parser);
+    let read: Point = serde::Deserialize::deserialize(&mut deserializer).unwrap();
+    assert_eq!(read, point(1, 2));
+    assert_eq!(deserializer.parser().depth(), 0);
+}
+
+#[test]
+fn pretty() {
+    let expected = "{\n  \"x\": 1,\n  \"y\": 2,\n  \"label\": null\n}";
+    assert_eq!(to_string_pretty(&point(1, 2)).unwrap(), expected);
+    let mut serializer = Serializer::pretty(Vec::new());
+    point(1, 2).serialize(&mut serializer).unwrap();
+    assert_eq!(serializer.into_inner(), expected.as_bytes());
+    assert_eq!(to_string_pretty(&Vec::<u8>::new()).unwrap(), "[]");
+
+    let mut serializer = Serializer::from_ser(Ser::pretty(Vec::new()).indent("\t"));
+    vec![1, 2].serialize(&mut serializer).unwrap();
+    assert_eq!(serializer.into_inner(), b"[\n\t1,\n\t2\n]");
+}
+
+#[test]
+fn non_finite() {
+    assert_eq!(to_string(&[f64::NAN, f64::INFINITY]).unwrap(), "[null,null]");
+    let mut serializer = Serializer::new(Vec::new()).non_finite(NonFinite::Error);
+    assert!(f64::NEG_INFINITY.serialize(&mut serializer).is_err());
+    let mut serializer = Serializer::new(Vec::new()).non_finite(NonFinite::Error);
+    1.5f32.serialize(&mut serializer).unwrap();
+    assert_eq!(serializer.into_inner(), b"1.5");
+}
+
+#[test]
+fn values_round_trip() {
+    let json = r#"{"a":[1,-2,1.5,"s",true,null],"b":{"c":{}}}"#;
+    let value: Value = from_str(json).unwrap();
+    assert_eq!(value, json::from_str(json).unwrap());
+    assert_eq!(to_string(&value).unwrap(), json);
+
+    // Typed data and values convert through each other's text.
+    let mut out = Vec::new();
+    to_writer(&mut out, &Point { x: 3, y: 4, label: None }).unwrap();
+    let value: Value = from_slice(&out).unwrap();
+    assert_eq!(value["x"], Value::from(3));
+}
+
+#[test]
+fn raw_values_serialize_as_their_value() {
+    let doc = json::de::De::new(r#"{"a": [1,  {"b": 2}], "c": 3}"#)
+        .raw_pointer("/a")
+        .unwrap()
+        .deserialization()
+        .unwrap();
+    assert_eq!(to_string(&doc).unwrap(), r#"{"a":[1,{"b":2}],"c":3}"#);
+    assert_eq!(to_string_pretty(&doc["a"]).unwrap(), "[\n  1,\n  {\n    \"b\": 2\n  }\n]");
+}
@@ -0,0 +1,157 @@
+use json_parser::json;
+use json_parser::json::Error;
+use json_parser::json::LineWriter;
+use json_parser::json::StreamDeserializer;
+use json_parser::json::Value;
+use json_parser::json::de::ErrorKind;
+use json_parser::json::de::ParserOptions;
+use json_parser::json::ser;
+use json_parser::json::ser::NonFinite;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+fn values(json: &str) -> Vec<Value> {
+    StreamDeserializer::new(json)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|err| panic!("{:?}: {}", json, err))
+}
+
+// Each record, or the kind of error and the line it was found on.
+fn records(stream: impl Iterator<Item = Result<Value, Error>>) -> Vec<Result<Value, (ErrorKind, usize)>> {
+    stream.map(|record| record.map_err(|err| (err.kind(), err.line()))).collect()
+}
+
+#[test]
+fn json_lines() {
+    let input = "{\"a\": 1}\n[2, 3]\n\"four\"\n5\nnull\n";
+    let expected = [value(r#"{"a": 1}"#), value("[2, 3]"), value(r#""four""#), value("5"), Value::Null];
+    assert_eq!(values(input), expected);
+    assert_eq!(values(&input.replace('\n', "\r\n")), expected);
+}
+
+#[test]
+fn blank_input_and_lines() {
+    assert!(values("").is_empty());
+    assert!(values(" \n\t\r\n").is_empty());
+    assert_eq!(values("\n\n1\n\n\n2\n\n"), [value("1"), value("2")]);
+}
+
+#[test]
+fn concatenated_values() {
+    let expected = [value("{}"), value("{}"), value("[]"), value(r#""a""#), value("[1]")];
+    assert_eq!(values(r#"{}{}[]"a"[1]"#), expected);
+    assert_eq!(values(r#"1"a"2[3]4{}true"#).len(), 7);
+    assert_eq!(values("1 true\tnull\nfalse"), [value("1"), value("true"), Value::Null, value("false")]);
+}
+
+#[test]
+fn scalars_need_a_delimiter() {
+    let cases = [
+        ("1true", 1),
+        ("true1", 4),
+        ("nullfalse", 4),
+        ("1 truenull", 6),
+        ("-1.5e3x", 6),
+    ];
+    for (json, offset) in cases {
+        let mut stream = StreamDeserializer::new(json);
+        let err = loop {
+            match stream.next() {
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => break err,
+                None => panic!("{:?} read without an error", json),
+            }
+        };
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::UnexpectedCharacter, offset), "{:?}", json);
+        assert!(stream.next().is_none(), "{:?}", json);
+    }
+    let errors = StreamDeserializer::new("1true\nnullfalse").filter(Result::is_err).count();
+    assert_eq!(errors, 1);
+}
+
+#[test]
+fn single_values_still_end_the_input() {
+    assert_eq!(json::from_str("1true").unwrap_err().kind(), ErrorKind::TrailingCharacters);
+    assert_eq!(json::from_str("1 2").unwrap_err().kind(), ErrorKind::TrailingCharacters);
+}
+
+#[test]
+fn stops_after_the_first_error() {
+    let stream = StreamDeserializer::new("1\n[2,\n3\n");
+    assert_eq!(records(stream), [Ok(value("1")), Err((ErrorKind::UnexpectedEof, 4))]);
+    let stream = StreamDeserializer::new("1\n{]\n3\n");
+    assert_eq!(records(stream), [Ok(value("1")), Err((ErrorKind::UnexpectedCharacter, 2))]);
+}
+
+#[test]
+fn skip_invalid() {
+    let input = "1\n{]\n[3]\n\"unterminated\n4\n5x 6\n7";
+    let stream = StreamDeserializer::new(input).skip_invalid(true);
+    assert_eq!(records(stream), [
+        Ok(value("1")),
+        Err((ErrorKind::UnexpectedCharacter, 2)),
+        Ok(value("[3]")),
+        Err((ErrorKind::UnexpectedCharacter, 4)),
+        Ok(value("4")),
+        Err((ErrorKind::UnexpectedCharacter, 6)),
+        Ok(value("7")),
+    ]);
+}
+
+#[test]
+fn skip_invalid_across_lines() {
+    // The unclosed array is only found to be bad once `3` follows `2`, and
+    // that line is skipped along with it.
+    let stream = StreamDeserializer::new("[1,\n2\n3\n4").skip_invalid(true);
+    assert_eq!(records(stream), [Err((ErrorKind::UnexpectedCharacter, 3)), Ok(value("4"))]);
+}
+
+#[test]
+fn options_and_position() {
+    let options = ParserOptions::new().max_elements(1);
+    let stream = StreamDeserializer::with_options("[1]\n[1, 2]\n[3]", options).skip_invalid(true);
+    assert_eq!(records(stream), [Ok(value("[1]")), Err((ErrorKind::TooManyElements, 2)), Ok(value("[3]"))]);
+
+    let mut stream = StreamDeserializer::new("1\n22 ");
+    stream.next();
+    assert_eq!(stream.offset(), 1);
+    stream.next();
+    assert_eq!((stream.position().line, stream.offset()), (2, 4));
+}
+
+#[test]
+fn from_reader() {
+    let input: &[u8] = b"{\"a\": \"\xc3\xa9\"}\n[1]\n2";
+    let read: Vec<Value> = StreamDeserializer::from_reader(input).collect::<Result<_, _>>().unwrap();
+    assert_eq!(read, values(std::str::from_utf8(input).unwrap()));
+}
+
+#[test]
+fn line_writer() {
+    let list = [value(r#"{"a": [1, 2]}"#), value(r#""x\ny""#), Value::Null, value("1.5")];
+    let mut writer = LineWriter::new(Vec::new());
+    for value in &list {
+        writer.write(value).unwrap();
+    }
+    writer.flush().unwrap();
+    let written = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(written, "{\"a\":[1,2]}\n\"x\\ny\"\nnull\n1.5\n");
+    assert_eq!(values(&written), list);
+
+    let mut lines = Vec::new();
+    ser::to_writer_lines(&mut lines, &list).unwrap();
+    assert_eq!(lines, written.as_bytes());
+}
+
+#[test]
+fn line_writer_non_finite() {
+    let nan = Value::from(f64::NAN);
+    let mut writer = LineWriter::new(Vec::new());
+    writer.write(&nan).unwrap();
+    assert_eq!(writer.into_inner(), b"null\n");
+
+    let mut writer = LineWriter::new(Vec::new()).non_finite(NonFinite::Error);
+    assert!(writer.write(&nan).is_err());
+}
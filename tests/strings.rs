@@ -0,0 +1,98 @@
+use json_parser::json;
+use json_parser::json::Value;
+use json_parser::json::de::ErrorKind;
+
+fn string(json: &str) -> String {
+    match json::from_str(json) {
+        Ok(Value::String(ref str)) => str.clone(),
+        other => panic!("{:?} parsed as {:?}", json, other),
+    }
+}
+
+fn error(json: &str) -> (ErrorKind, usize) {
+    match json::from_str(json) {
+        Ok(value) => panic!("{:?} parsed as {:?}", json, value),
+        Err(err) => (err.kind(), err.offset()),
+    }
+}
+
+#[test]
+fn plain() {
+    assert_eq!(string(r#""""#), "");
+    assert_eq!(string(r#""street_2""#), "street_2");
+    assert_eq!(string(r#""+44 1234567""#), "+44 1234567");
+    assert_eq!(string("\"caf\u{e9} \u{1f600}\""), "caf\u{e9} \u{1f600}");
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(string(r#""\" \\ \/ \b \f \n \r \t""#), "\" \\ / \u{8} \u{c} \n \r \t");
+    assert_eq!(string(r#""\u0041\u00e9\u20AC""#), "A\u{e9}\u{20ac}");
+    assert_eq!(string(r#""\u0000""#), "\u{0}");
+}
+
+#[test]
+fn surrogate_pairs() {
+    assert_eq!(string(r#""\ud83d\ude00""#), "\u{1f600}");
+    assert_eq!(string(r#""\uD834\uDD1E""#), "\u{1d11e}");
+}
+
+#[test]
+fn lone_surrogates() {
+    assert_eq!(error(r#""\ud800""#).0, ErrorKind::LoneSurrogate);
+    assert_eq!(error(r#""\udc00""#).0, ErrorKind::LoneSurrogate);
+    assert_eq!(error(r#""\ud800\u0041""#).0, ErrorKind::LoneSurrogate);
+    assert_eq!(error(r#""\ud800x""#).0, ErrorKind::LoneSurrogate);
+}
+
+#[test]
+fn invalid_escapes() {
+    assert_eq!(error(r#""\x""#), (ErrorKind::InvalidEscape, 2));
+    assert_eq!(error(r#""\'""#), (ErrorKind::InvalidEscape, 2));
+    assert_eq!(error(r#""\u12g4""#).0, ErrorKind::InvalidEscape);
+}
+
+#[test]
+fn control_characters() {
+    for c in (0..0x20u8).map(char::from) {
+        let json = format!("\"a{}\"", c);
+        assert_eq!(error(&json), (ErrorKind::UnexpectedCharacter, 2), "{:?}", json);
+    }
+    assert_eq!(string("\"\u{7f}\""), "\u{7f}");
+}
+
+#[test]
+fn unterminated() {
+    assert_eq!(error(r#""abc"#).0, ErrorKind::UnexpectedEof);
+    assert_eq!(error(r#""abc\"#).0, ErrorKind::UnexpectedEof);
+    assert_eq!(error(r#""\u12"#).0, ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn invalid_utf8() {
+    let inputs: &[&[u8]] = &[
+        // Overlong encodings of '/' and NUL.
+        b"\"\xc0\xaf\"",
+        b"\"\xe0\x80\xaf\"",
+        b"\"\xf0\x80\x80\xaf\"",
+        // An encoded surrogate, and a code point past U+10FFFF.
+        b"\"\xed\xa0\x80\"",
+        b"\"\xf4\x90\x80\x80\"",
+        // A continuation byte with no lead, and a truncated sequence.
+        b"\"\x80\"",
+        b"\"\xe2\x82\"",
+    ];
+    for input in inputs {
+        let err = json::from_slice(input).unwrap_err();
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::InvalidUtf8, 1), "{:?}", input);
+        let err = json::from_reader(*input).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidUtf8, "{:?}", input);
+    }
+}
+
+#[test]
+fn round_trip() {
+    let original = "\"quote\" \\ / \u{8}\u{c}\n\r\t \u{1} \u{1f} \u{e9} \u{1f600}";
+    let written = json::to_string(&Value::String(String::from(original))).unwrap();
+    assert_eq!(string(&written), original);
+}
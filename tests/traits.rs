@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+
+use json_parser::json;
+use json_parser::json::BorrowedValue;
+use json_parser::json::Map;
+use json_parser::json::Value;
+use json_parser::json::element::Element;
+use json_parser::json::value::Num;
+use json_parser::json::value::Number;
+
+fn value(json: &str) -> Value {
+    json::from_str(json).unwrap()
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(Value::default(), Value::Null);
+    assert_eq!(BorrowedValue::default(), BorrowedValue::Null);
+    assert_eq!(Number::default(), Number::from(0u8));
+    assert!(matches!(Num::default(), Num::PosInt(0)));
+    assert!(Map::default().is_empty());
+    assert_eq!(*Element::default().value(), Value::Null);
+}
+
+#[test]
+fn debug() {
+    assert_eq!(format!("{:?}", Number::from(7u8)), "Number(7)");
+    assert_eq!(format!("{:?}", Number::from(-7)), "Number(-7)");
+    assert_eq!(format!("{:?}", Number::from(1.0)), "Number(1.0)");
+    assert_eq!(format!("{:?}", value(r#"["a", true]"#)), r#"Array([String("a"), Boolean(true)])"#);
+    assert_eq!(format!("{:?}", value(r#"{"k": null}"#)), r#"Object({"k": Null})"#);
+    assert_eq!(format!("{:?}", Element::new(Value::Null)), "Element { value: Null }");
+}
+
+#[test]
+fn clones_are_equal_and_independent() {
+    let original = value(r#"{"a": [1, {"b": "c"}]}"#);
+    let mut copy = original.clone();
+    assert_eq!(copy, original);
+    copy["a"][1]["b"] = Value::from("d");
+    assert_ne!(copy, original);
+    assert_eq!(original["a"][1]["b"], Value::from("c"));
+
+    let element = Element::new(original.clone());
+    assert_eq!(element.clone(), element);
+    assert_eq!(element.into_value(), original);
+}
+
+#[test]
+fn values_equal_by_content() {
+    assert_eq!(value(r#"{"a": 1, "b": 2}"#), value(r#"{"b": 2, "a": 1}"#));
+    assert_ne!(value("[1, 2]"), value("[2, 1]"));
+    assert_eq!(value("1"), value("1.0"));
+    assert_ne!(value("1"), value(r#""1""#));
+    assert_ne!(value("null"), value("false"));
+    assert_ne!(value("[]"), value("{}"));
+    // Borrowed objects are lists of members, so their order counts.
+    let a = json::from_str_borrowed(r#"{"a": 1, "b": 2}"#).unwrap();
+    let b = json::from_str_borrowed(r#"{"b": 2, "a": 1}"#).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a, json::from_str_borrowed(r#"{"a": 1.0, "b": 2}"#).unwrap());
+}
+
+#[test]
+fn numbers_equal_by_value() {
+    assert_eq!(Number::from(1u8), Number::from(1.0));
+    assert_eq!(Number::from(0.0), Number::from(-0.0));
+    assert_eq!(Number::from(-3i8), Number::from(-3.0f32));
+    assert_ne!(Number::from(1u8), Number::from(1.5));
+    // Compared exactly, not as floats.
+    assert_ne!(Number::from(9_007_199_254_740_993u64), Number::from(9_007_199_254_740_992.0));
+    assert_eq!(Number::from(f64::NAN), Number::from(-f64::NAN));
+    assert_eq!(Num::PosInt(2), Num::Float(2.0));
+}
+
+#[test]
+fn numbers_are_totally_ordered() {
+    let sorted = [
+        Number::from(f64::NEG_INFINITY),
+        Number::from(i64::MIN),
+        Number::from(-1.5),
+        Number::from(-1),
+        Number::from(0u8),
+        Number::from(0.5),
+        Number::from(9_007_199_254_740_992.0),
+        Number::from(9_007_199_254_740_993u64),
+        Number::from(u64::MAX),
+        Number::from(f64::INFINITY),
+        Number::from(f64::NAN),
+    ];
+    for pair in sorted.windows(2) {
+        assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+    }
+    let mut shuffled = sorted.to_vec();
+    shuffled.reverse();
+    shuffled.sort();
+    assert_eq!(shuffled, sorted);
+    assert_eq!(Number::from(2u8).max(Number::from(2.5)), Number::from(2.5));
+}
+
+#[test]
+fn numbers_hash_like_they_compare() {
+    let set: HashSet<Number> = [
+        Number::from(1u8),
+        Number::from(1.0),
+        Number::from(-1),
+        Number::from(-1.0),
+        Number::from(0.0),
+        Number::from(-0.0),
+        Number::from(f64::NAN),
+        Number::from(f64::NAN),
+        Number::from(1.5),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    assert_eq!(set.len(), 5);
+    assert!(set.contains(&Number::from(1i64)));
+    let set: BTreeSet<Number> = set.into_iter().collect();
+    assert_eq!(set.iter().next(), Some(&Number::from(-1)));
+}
+
+#[test]
+fn numbers_from_str() {
+    assert_eq!("42".parse::<Number>().unwrap(), Number::from(42u8));
+    assert_eq!("-0.5e1".parse::<Number>().unwrap(), Number::from(-5.0));
+    for str in ["", " 1", "+1", "01", "1.", ".5", "1e", "0x10", "NaN"] {
+        assert!(str.parse::<Number>().is_err(), "{:?}", str);
+    }
+}
+
+#[test]
+fn display() {
+    let doc = value(r#"{"a": [1, "x"], "b": {}}"#);
+    assert_eq!(doc.to_string(), r#"{"a":[1,"x"],"b":{}}"#);
+    assert_eq!(format!("{:#}", doc), "{\n  \"a\": [\n    1,\n    \"x\"\n  ],\n  \"b\": {}\n}");
+    assert_eq!(format!("{}", Value::Null), "null");
+}